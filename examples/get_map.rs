@@ -1,10 +1,8 @@
-use linux_maps_parser::parse;
-use std::process;
+use linux_maps_parser::parse_self;
+use std::error::Error;
 
-fn main() {
-    let pid = process::id();
-    match parse(pid) {
-        Ok(p) => println!("{:?}", p),
-        Err(e) => println!("Error while parsing: {:?}", e),
-    }
+fn main() -> Result<(), Box<dyn Error>> {
+    let entries = parse_self()?;
+    println!("{:?}", entries);
+    Ok(())
 }
@@ -0,0 +1,259 @@
+use crate::{Address, Entries, Entry, Error, Permissions, Result};
+
+// Layout, all integers little-endian:
+//
+//   u32 entry count, then per entry:
+//   u64 start, u64 end, u64 offset, u64 inode, u32 dev_maj, u32 dev_min,
+//   u8 perms (r = 4, w = 2, x = 1, shared = 8),
+//   u8 flags (deleted = 1, has path = 2),
+//   if has path: u32 length followed by that many bytes of UTF-8.
+const SHARED: u8 = 8;
+const DELETED: u8 = 1;
+const HAS_PATH: u8 = 2;
+
+// Size of an entry without its path.
+const FIXED_LEN: usize = 42;
+
+impl Entries {
+    /// Encodes the entries in a compact binary form that `from_bytes` reads
+    /// back.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut out = Vec::with_capacity(4 + self.len() * FIXED_LEN);
+        out.extend_from_slice(&(self.len() as u32).to_le_bytes());
+
+        for entry in self {
+            out.extend_from_slice(&entry.start_addr.0.to_le_bytes());
+            out.extend_from_slice(&entry.end_addr.0.to_le_bytes());
+            out.extend_from_slice(&entry.offset.to_le_bytes());
+            out.extend_from_slice(&entry.inode.to_le_bytes());
+            out.extend_from_slice(&entry.dev_maj.to_le_bytes());
+            out.extend_from_slice(&entry.dev_min.to_le_bytes());
+            out.push(entry.perms.to_octal() | if entry.perms.shared { SHARED } else { 0 });
+
+            let mut flags = 0;
+            if entry.deleted {
+                flags |= DELETED;
+            }
+            if entry.path.is_some() {
+                flags |= HAS_PATH;
+            }
+            out.push(flags);
+
+            if let Some(path) = &entry.path {
+                out.extend_from_slice(&(path.len() as u32).to_le_bytes());
+                out.extend_from_slice(path.as_bytes());
+            }
+        }
+
+        out
+    }
+
+    pub fn from_bytes(data: &[u8]) -> Result<Entries> {
+        let mut reader = Reader { data, pos: 0 };
+        let count = reader.u32()?;
+        let mut entries = Vec::with_capacity((count as usize).min(data.len() / FIXED_LEN));
+
+        for _ in 0..count {
+            let start_addr = Address(reader.u64()?);
+            let end_addr = Address(reader.u64()?);
+            let offset = reader.u64()?;
+            let inode = reader.u64()?;
+            let dev_maj = reader.u32()?;
+            let dev_min = reader.u32()?;
+            let perms = reader.u8()?;
+            let flags = reader.u8()?;
+
+            let path = match flags & HAS_PATH {
+                0 => None,
+                _ => {
+                    let at = reader.pos;
+                    let len = reader.u32()? as usize;
+                    let bytes = reader.take(len)?;
+                    let path = std::str::from_utf8(bytes)
+                        .map_err(|_| Error::MalformedBinary { offset: at })?;
+                    Some(path.to_string())
+                }
+            };
+
+            entries.push(Entry {
+                start_addr,
+                end_addr,
+                perms: Permissions::from_bits(
+                    perms & 4 != 0,
+                    perms & 2 != 0,
+                    perms & 1 != 0,
+                    perms & SHARED != 0,
+                ),
+                offset,
+                dev_maj,
+                dev_min,
+                inode,
+                path,
+                deleted: flags & DELETED != 0,
+            });
+        }
+
+        match reader.pos == data.len() {
+            true => Ok(Entries::from_vec(entries)),
+            false => Err(Error::MalformedBinary { offset: reader.pos }),
+        }
+    }
+}
+
+/// Decodes the payload of an ELF core dump's `NT_FILE` note, as written for
+/// 64-bit little-endian targets. The note does not record permissions or
+/// device numbers, so entries come back as `---p` with zeroed device and
+/// inode fields.
+pub fn parse_nt_file(data: &[u8]) -> Result<Entries> {
+    let mut reader = Reader { data, pos: 0 };
+    let count = reader.u64()?;
+    let page_size = reader.u64()?;
+
+    let mut ranges = Vec::with_capacity((count as usize).min(data.len() / 24));
+    for _ in 0..count {
+        let at = reader.pos;
+        let start = reader.u64()?;
+        let end = reader.u64()?;
+        let offset = reader
+            .u64()?
+            .checked_mul(page_size)
+            .ok_or(Error::MalformedBinary { offset: at })?;
+        if end < start {
+            return Err(Error::MalformedBinary { offset: at });
+        }
+        ranges.push((start, end, offset));
+    }
+
+    let mut entries = Vec::with_capacity(ranges.len());
+    for (start, end, offset) in ranges {
+        let at = reader.pos;
+        let len = data[at..]
+            .iter()
+            .position(|&b| b == 0)
+            .ok_or(Error::MalformedBinary { offset: at })?;
+        let path = std::str::from_utf8(reader.take(len)?)
+            .map_err(|_| Error::MalformedBinary { offset: at })?;
+        reader.take(1)?;
+
+        entries.push(Entry {
+            start_addr: Address(start),
+            end_addr: Address(end),
+            perms: Permissions::none(),
+            offset,
+            dev_maj: 0,
+            dev_min: 0,
+            inode: 0,
+            path: Some(path.to_string()),
+            deleted: false,
+        });
+    }
+
+    Ok(Entries::from_vec(entries))
+}
+
+struct Reader<'a> {
+    data: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn take(&mut self, len: usize) -> Result<&'a [u8]> {
+        let bytes = self
+            .pos
+            .checked_add(len)
+            .and_then(|end| self.data.get(self.pos..end))
+            .ok_or(Error::MalformedBinary { offset: self.pos })?;
+        self.pos += len;
+        Ok(bytes)
+    }
+
+    fn u8(&mut self) -> Result<u8> {
+        Ok(self.take(1)?[0])
+    }
+
+    fn u32(&mut self) -> Result<u32> {
+        Ok(u32::from_le_bytes(self.take(4)?.try_into().unwrap()))
+    }
+
+    fn u64(&mut self) -> Result<u64> {
+        Ok(u64::from_le_bytes(self.take(8)?.try_into().unwrap()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{parse_from_reader, parse_nt_file, Entries, Error};
+    use std::io::Cursor;
+
+    const MAPS: &str = "\
+55d0c0a00000-55d0c0a02000 r--p 00000000 08:01 1048602 /usr/bin/cat
+55d0c1c4e000-55d0c1c6f000 rw-p 00000000 00:00 0 [heap]
+7f3a1c028000-7f3a1c19d000 r-xs 00028000 fd:03 1054201 /tmp/jit (deleted)
+7f3a1c1a4000-7f3a1c1a8000 rw-p 00000000 00:00 0
+";
+
+    #[test]
+    fn round_trip() {
+        let parsed = parse_from_reader(Cursor::new(MAPS)).unwrap();
+        let bytes = parsed.to_bytes();
+        let decoded = Entries::from_bytes(&bytes).unwrap();
+
+        assert_eq!(decoded.as_slice(), parsed.as_slice());
+        assert!(decoded[2].deleted);
+        assert!(decoded[2].perms.shared);
+        assert_eq!(decoded[3].path, None);
+    }
+
+    #[test]
+    fn malformed() {
+        let bytes = parse_from_reader(Cursor::new(MAPS)).unwrap().to_bytes();
+
+        assert!(matches!(
+            Entries::from_bytes(&bytes[..bytes.len() - 1]),
+            Err(Error::MalformedBinary { .. })
+        ));
+        assert!(matches!(
+            Entries::from_bytes(&[bytes.as_slice(), &[0]].concat()),
+            Err(Error::MalformedBinary { .. })
+        ));
+        assert!(Entries::from_bytes(&[]).is_err());
+        assert!(Entries::from_bytes(&0u32.to_le_bytes()).unwrap().is_empty());
+    }
+
+    fn nt_file(files: &[(u64, u64, u64, &str)]) -> Vec<u8> {
+        let mut data = vec![];
+        data.extend_from_slice(&(files.len() as u64).to_le_bytes());
+        data.extend_from_slice(&0x1000u64.to_le_bytes());
+        for (start, end, pages, _) in files {
+            data.extend_from_slice(&start.to_le_bytes());
+            data.extend_from_slice(&end.to_le_bytes());
+            data.extend_from_slice(&pages.to_le_bytes());
+        }
+        for (.., name) in files {
+            data.extend_from_slice(name.as_bytes());
+            data.push(0);
+        }
+        data
+    }
+
+    #[test]
+    fn nt_file_note() {
+        let data = nt_file(&[
+            (0x55d0c0a00000, 0x55d0c0a02000, 0, "/usr/bin/cat"),
+            (0x7f3a1c028000, 0x7f3a1c19d000, 0x28, "/usr/lib/libc.so.6"),
+        ]);
+        let parsed = parse_nt_file(&data).unwrap();
+
+        assert_eq!(parsed.len(), 2);
+        assert_eq!(parsed[0].start_addr, 0x55d0c0a00000);
+        assert_eq!(parsed[0].end_addr, 0x55d0c0a02000);
+        assert_eq!(parsed[0].path.as_deref(), Some("/usr/bin/cat"));
+        assert_eq!(parsed[1].offset, 0x28000);
+        assert_eq!(parsed[1].path.as_deref(), Some("/usr/lib/libc.so.6"));
+        assert!(!parsed[1].is_readable());
+
+        assert!(parse_nt_file(&data[..data.len() - 1]).is_err());
+        assert!(parse_nt_file(&data[..20]).is_err());
+        assert!(parse_nt_file(&nt_file(&[])).unwrap().is_empty());
+    }
+}
@@ -0,0 +1,785 @@
+use alloc::{
+    format,
+    string::{String, ToString},
+    vec::Vec,
+};
+use core::{
+    fmt::{Debug, Display, LowerHex, UpperHex},
+    ops::{Add, Sub},
+    str::FromStr,
+};
+
+pub type Result<T> = core::result::Result<T, Error>;
+
+#[derive(Debug)]
+pub enum Error {
+    MapsFileDoesNotExist,
+    /// The maps file exists but may not be read, typically because the
+    /// process belongs to another user.
+    PermissionDenied,
+    #[cfg(feature = "std")]
+    FileOpenError(std::io::Error),
+    IntParseError {
+        line: usize,
+        source: core::num::ParseIntError,
+    },
+    MalformedAddress {
+        line: usize,
+        token: String,
+    },
+    MalformedPermissions {
+        line: usize,
+        token: String,
+    },
+    MalformedInode {
+        line: usize,
+        token: String,
+    },
+    MalformedDevice {
+        line: usize,
+        token: String,
+    },
+    MalformedOffset {
+        line: usize,
+        token: String,
+    },
+    TooFewFields {
+        line: usize,
+        count: usize,
+    },
+    InvalidRange {
+        line: usize,
+        start: u64,
+        end: u64,
+    },
+    InvalidPid(String),
+    MalformedBinary {
+        offset: usize,
+    },
+    /// `/proc/sys/vm/max_map_count` could not be opened, read or parsed.
+    #[cfg(feature = "std")]
+    MaxMapCountUnavailable(std::io::Error),
+}
+
+impl Error {
+    // Token parsers don't know where they are in the file, so they report
+    // line 0 and the line loop fills in the real 1-based line number.
+    pub(crate) fn with_line(mut self, at: usize) -> Self {
+        match &mut self {
+            Error::IntParseError { line, .. }
+            | Error::MalformedAddress { line, .. }
+            | Error::MalformedPermissions { line, .. }
+            | Error::MalformedInode { line, .. }
+            | Error::MalformedDevice { line, .. }
+            | Error::MalformedOffset { line, .. }
+            | Error::InvalidRange { line, .. }
+            | Error::TooFewFields { line, .. } => *line = at,
+            Error::MapsFileDoesNotExist
+            | Error::PermissionDenied
+            | Error::InvalidPid(_)
+            | Error::MalformedBinary { .. } => {}
+            #[cfg(feature = "std")]
+            Error::FileOpenError(_) | Error::MaxMapCountUnavailable(_) => {}
+        }
+        self
+    }
+
+    // The process can exit between the existence check and the open, so a
+    // missing file is still possible at this point.
+    #[cfg(feature = "std")]
+    pub(crate) fn from_open(e: std::io::Error) -> Self {
+        match e.kind() {
+            std::io::ErrorKind::NotFound => Error::MapsFileDoesNotExist,
+            std::io::ErrorKind::PermissionDenied => Error::PermissionDenied,
+            _ => Error::FileOpenError(e),
+        }
+    }
+}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Error::MapsFileDoesNotExist => write!(f, "maps file does not exist"),
+            Error::PermissionDenied => write!(f, "permission denied reading maps file"),
+            #[cfg(feature = "std")]
+            Error::FileOpenError(e) => write!(f, "could not open maps file: {}", e),
+            Error::IntParseError { line, source } => {
+                write!(f, "line {}: invalid integer: {}", line, source)
+            }
+            Error::MalformedAddress { line, token } => {
+                write!(f, "line {}: malformed address range '{}'", line, token)
+            }
+            Error::MalformedPermissions { line, token } => {
+                write!(f, "line {}: malformed permissions '{}'", line, token)
+            }
+            Error::MalformedInode { line, token } => {
+                write!(f, "line {}: malformed inode '{}'", line, token)
+            }
+            Error::MalformedDevice { line, token } => {
+                write!(f, "line {}: malformed device '{}'", line, token)
+            }
+            Error::MalformedOffset { line, token } => {
+                write!(f, "line {}: malformed offset '{}'", line, token)
+            }
+            Error::TooFewFields { line, count } => {
+                write!(
+                    f,
+                    "line {}: expected at least 5 fields, found {}",
+                    line, count
+                )
+            }
+            Error::InvalidRange { line, start, end } => write!(
+                f,
+                "line {}: end address 0x{:x} is below start address 0x{:x}",
+                line, end, start
+            ),
+            Error::InvalidPid(pid) => write!(f, "invalid pid '{}'", pid),
+            Error::MalformedBinary { offset } => {
+                write!(f, "malformed binary entries at byte {}", offset)
+            }
+            #[cfg(feature = "std")]
+            Error::MaxMapCountUnavailable(e) => {
+                write!(f, "could not read vm.max_map_count: {}", e)
+            }
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::FileOpenError(e) | Error::MaxMapCountUnavailable(e) => Some(e),
+            Error::IntParseError { source, .. } => Some(source),
+            _ => None,
+        }
+    }
+}
+
+/// A virtual address. `Display` and `Debug` print it as `0x...`, while the hex
+/// format traits behave exactly like `u64`'s so padding and `{:#x}` still work.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(transparent)
+)]
+pub struct Address(pub u64);
+
+impl Address {
+    pub fn as_u64(self) -> u64 {
+        self.0
+    }
+}
+
+impl From<u64> for Address {
+    fn from(addr: u64) -> Self {
+        Address(addr)
+    }
+}
+
+impl From<Address> for u64 {
+    fn from(addr: Address) -> Self {
+        addr.0
+    }
+}
+
+impl PartialEq<u64> for Address {
+    fn eq(&self, other: &u64) -> bool {
+        self.0 == *other
+    }
+}
+
+impl PartialOrd<u64> for Address {
+    fn partial_cmp(&self, other: &u64) -> Option<core::cmp::Ordering> {
+        self.0.partial_cmp(other)
+    }
+}
+
+impl PartialEq<Address> for u64 {
+    fn eq(&self, other: &Address) -> bool {
+        *self == other.0
+    }
+}
+
+impl PartialOrd<Address> for u64 {
+    fn partial_cmp(&self, other: &Address) -> Option<core::cmp::Ordering> {
+        self.partial_cmp(&other.0)
+    }
+}
+
+impl Sub for Address {
+    type Output = u64;
+
+    fn sub(self, other: Address) -> u64 {
+        self.0 - other.0
+    }
+}
+
+impl Add<u64> for Address {
+    type Output = Address;
+
+    fn add(self, other: u64) -> Address {
+        Address(self.0 + other)
+    }
+}
+
+impl Sub<u64> for Address {
+    type Output = Address;
+
+    fn sub(self, other: u64) -> Address {
+        Address(self.0 - other)
+    }
+}
+
+impl Display for Address {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "0x{:x}", self.0)
+    }
+}
+
+impl Debug for Address {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        Display::fmt(self, f)
+    }
+}
+
+impl LowerHex for Address {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        LowerHex::fmt(&self.0, f)
+    }
+}
+
+impl UpperHex for Address {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        UpperHex::fmt(&self.0, f)
+    }
+}
+
+// Field order matters for the derived `Ord`: entries sort by start address
+// first, then by the remaining fields.
+#[derive(Clone, Default, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Entry {
+    pub start_addr: Address,
+    pub end_addr: Address,
+    pub perms: Permissions,
+    pub offset: u64,
+    pub dev_maj: u32,
+    pub dev_min: u32,
+    pub inode: u64,
+    pub path: Option<String>,
+    pub deleted: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Region {
+    File(String),
+    Heap,
+    Stack,
+    /// A thread's stack, as `[stack:TID]` on older kernels.
+    ThreadStack(u32),
+    Vdso,
+    Vvar,
+    Vsyscall,
+    Anonymous,
+    Other(String),
+}
+
+impl Entry {
+    pub fn region(&self) -> Region {
+        match self.path.as_deref() {
+            None => Region::Anonymous,
+            Some("[heap]") => Region::Heap,
+            Some("[stack]") => Region::Stack,
+            Some(v) if v.starts_with("[stack:") => v
+                .strip_prefix("[stack:")
+                .and_then(|v| v.strip_suffix(']'))
+                .and_then(|tid| tid.parse().ok())
+                .map_or_else(|| Region::Other(v.to_string()), Region::ThreadStack),
+            Some("[vdso]") => Region::Vdso,
+            Some("[vvar]") => Region::Vvar,
+            Some("[vsyscall]") => Region::Vsyscall,
+            Some(v) if v.starts_with("[anon:") => Region::Anonymous,
+            Some(v) if v.starts_with('[') => Region::Other(v.to_string()),
+            Some(v) => Region::File(v.to_string()),
+        }
+    }
+
+    pub fn size(&self) -> u64 {
+        self.end_addr.0.saturating_sub(self.start_addr.0)
+    }
+
+    /// `size()` in binary units the way `pmap` shows it, e.g. `4.0K` or
+    /// `1.5G`.
+    pub fn size_human(&self) -> String {
+        human_size(self.size())
+    }
+
+    /// Returns 0 when `page_size` is 0.
+    pub fn page_count(&self, page_size: u64) -> u64 {
+        match page_size {
+            0 => 0,
+            _ => self.size().div_ceil(page_size),
+        }
+    }
+
+    pub fn page_count_default(&self) -> u64 {
+        self.page_count(DEFAULT_PAGE_SIZE)
+    }
+
+    pub fn is_page_aligned(&self, page_size: u64) -> bool {
+        page_size != 0
+            && align_down(self.start_addr.0, page_size) == self.start_addr.0
+            && align_down(self.offset, page_size) == self.offset
+    }
+
+    pub fn contains(&self, addr: u64) -> bool {
+        self.start_addr <= addr && addr < self.end_addr
+    }
+
+    // glibc's makedev(3) encoding
+    pub fn dev(&self) -> u64 {
+        let major = self.dev_maj as u64;
+        let minor = self.dev_min as u64;
+
+        ((major & 0xfffff000) << 32)
+            | ((major & 0x00000fff) << 8)
+            | ((minor & 0xffffff00) << 12)
+            | (minor & 0x000000ff)
+    }
+
+    pub fn is_anonymous(&self) -> bool {
+        self.inode == 0
+            && match &self.path {
+                None => true,
+                Some(path) => path.starts_with('['),
+            }
+    }
+
+    pub fn is_file_backed(&self) -> bool {
+        !self.is_anonymous()
+    }
+
+    pub fn offset_hex(&self) -> String {
+        format!("0x{:x}", self.offset)
+    }
+
+    pub fn file_offset(&self, addr: u64) -> Option<u64> {
+        if !self.contains(addr) {
+            return None;
+        }
+
+        self.offset.checked_add(addr - self.start_addr.0)
+    }
+
+    pub fn is_readable(&self) -> bool {
+        self.perms.read
+    }
+
+    pub fn is_writable(&self) -> bool {
+        self.perms.write
+    }
+
+    pub fn is_executable(&self) -> bool {
+        self.perms.execute
+    }
+
+    pub fn is_wx(&self) -> bool {
+        self.perms.write && self.perms.execute
+    }
+
+    pub fn perms_octal(&self) -> u8 {
+        self.perms.to_octal()
+    }
+}
+
+impl Debug for Entry {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "Entry {{ start_addr: 0x{:x}, end_addr: 0x{:x}, permissions: {:?}, offset: 0x{:x}, dev_maj: {}, dev_min: {}, inode: {}, path: {:?}, deleted: {} }}",
+            self.start_addr, self.end_addr, self.perms, self.offset,
+            self.dev_maj, self.dev_min, self.inode, self.path, self.deleted)
+    }
+}
+
+/// Parses a single maps line.
+///
+/// ```
+/// use linux_maps_parser::Entry;
+///
+/// let entry = Entry::try_from(
+///     "7f3a1c028000-7f3a1c19d000 r-xp 00000000 08:01 1234 /usr/lib/libc.so.6",
+/// )
+/// .unwrap();
+/// assert_eq!(entry.path.as_deref(), Some("/usr/lib/libc.so.6"));
+/// assert!(entry.is_executable());
+/// ```
+impl TryFrom<&str> for Entry {
+    type Error = Error;
+
+    fn try_from(line: &str) -> Result<Self> {
+        parse_line(line)
+    }
+}
+
+impl Display for Entry {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        let header = format!(
+            "{:08x}-{:08x} {} {:08x} {:02x}:{:02x} {} ",
+            self.start_addr,
+            self.end_addr,
+            self.perms,
+            self.offset,
+            self.dev_maj,
+            self.dev_min,
+            self.inode
+        );
+
+        // the kernel pads the header to `25 + sizeof(void *) * 6 - 1` columns
+        // before the pathname, so this matches the layout of a kernel with the
+        // same pointer width as the target
+        let width = 25 + core::mem::size_of::<usize>() * 6 - 1;
+        match &self.path {
+            Some(path) if self.deleted => {
+                write!(f, "{:<width$} {} (deleted)", header, path, width = width)
+            }
+            Some(path) => write!(f, "{:<width$} {}", header, path, width = width),
+            None => write!(f, "{}", header),
+        }
+    }
+}
+
+#[derive(Clone, Default, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Permissions {
+    pub read: bool,
+    pub write: bool,
+    pub execute: bool,
+    pub shared: bool,
+}
+
+impl Debug for Permissions {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(
+            f,
+            "Permissions {{ read: {}, write: {}, execute: {}, shared: {} }}",
+            self.read, self.write, self.execute, self.shared
+        )
+    }
+}
+
+impl Permissions {
+    pub fn none() -> Permissions {
+        Permissions::from_bits(false, false, false, false)
+    }
+
+    pub fn from_bits(read: bool, write: bool, execute: bool, shared: bool) -> Permissions {
+        Permissions {
+            read,
+            write,
+            execute,
+            shared,
+        }
+    }
+
+    // rwx in the familiar chmod style: r = 4, w = 2, x = 1
+    pub fn to_octal(&self) -> u8 {
+        (self.read as u8) << 2 | (self.write as u8) << 1 | self.execute as u8
+    }
+
+    pub fn as_str(&self) -> String {
+        self.raw().to_string()
+    }
+
+    /// The four-character field as the kernel writes it, e.g. `r-xp`.
+    pub fn raw(&self) -> &'static str {
+        const RAW: [&str; 16] = [
+            "---p", "--xp", "-w-p", "-wxp", "r--p", "r-xp", "rw-p", "rwxp", "---s", "--xs", "-w-s",
+            "-wxs", "r--s", "r-xs", "rw-s", "rwxs",
+        ];
+
+        RAW[(self.shared as usize) << 3 | self.to_octal() as usize]
+    }
+}
+
+impl Display for Permissions {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.write_str(&self.as_str())
+    }
+}
+
+// Addresses are printed with at least 8 hex digits, so both 32-bit and 64-bit
+// processes are handled; anything wider than 16 digits cannot fit in a u64.
+// The kernel never prefixes its hex, but hand-written lines often do.
+// Uppercase digits need no special handling as `from_str_radix` accepts them.
+fn strip_hex_prefix(token: &str) -> &str {
+    token
+        .strip_prefix("0x")
+        .or_else(|| token.strip_prefix("0X"))
+        .unwrap_or(token)
+}
+
+// `from_str_radix` also takes a leading `+`, which no maps field may carry.
+fn is_hex(token: &str) -> bool {
+    !token.is_empty() && token.bytes().all(|b| b.is_ascii_hexdigit())
+}
+
+// A single address, as both ends of a maps range or the start of a
+// `numa_maps` line.
+pub(crate) fn parse_address(token: &str) -> Result<u64> {
+    let malformed = || Error::MalformedAddress {
+        line: 0,
+        token: token.to_string(),
+    };
+
+    let digits = strip_hex_prefix(token);
+    if digits.len() > 16 || !is_hex(digits) {
+        return Err(malformed());
+    }
+
+    u64::from_str_radix(digits, 16).map_err(|_| malformed())
+}
+
+pub(crate) fn parse_addresses(addresses: &str) -> Result<(u64, u64)> {
+    let malformed = || Error::MalformedAddress {
+        line: 0,
+        token: addresses.to_string(),
+    };
+
+    let splitaddr = addresses
+        .split('-')
+        .map(|str_val| parse_address(str_val).map_err(|_| malformed()))
+        .collect::<Result<Vec<u64>>>()?;
+
+    match splitaddr[..] {
+        [start, end] if end < start => Err(Error::InvalidRange {
+            line: 0,
+            start,
+            end,
+        }),
+        [start, end] => Ok((start, end)),
+        _ => Err(malformed()),
+    }
+}
+
+impl FromStr for Permissions {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let chars = s.chars().collect::<Vec<char>>();
+
+        match chars[..] {
+            [r @ ('r' | '-'), w @ ('w' | '-'), x @ ('x' | '-'), p @ ('p' | 's')] => {
+                Ok(Permissions {
+                    read: r == 'r',
+                    write: w == 'w',
+                    execute: x == 'x',
+                    shared: p == 's',
+                })
+            }
+            _ => Err(Error::MalformedPermissions {
+                line: 0,
+                token: s.to_string(),
+            }),
+        }
+    }
+}
+
+pub(crate) fn parse_params(params: &str) -> Result<Permissions> {
+    params.parse()
+}
+
+pub(crate) fn parse_offset(offset: &str) -> Result<u64> {
+    // Other stray characters are left to `from_str_radix`, whose error is
+    // kept as the source; only the `+` it would accept is caught here.
+    let digits = strip_hex_prefix(offset);
+    if digits.len() > 16 || digits.starts_with('+') {
+        return Err(Error::MalformedOffset {
+            line: 0,
+            token: offset.to_string(),
+        });
+    }
+
+    u64::from_str_radix(digits, 16).map_err(|source| Error::IntParseError { line: 0, source })
+}
+
+pub(crate) fn parse_inode(inode: &str) -> Result<u64> {
+    let malformed = || Error::MalformedInode {
+        line: 0,
+        token: inode.to_string(),
+    };
+
+    if !inode.bytes().all(|b| b.is_ascii_digit()) {
+        return Err(malformed());
+    }
+    inode.parse::<u64>().map_err(|_| malformed())
+}
+
+pub(crate) fn parse_device(device: &str) -> Result<(u32, u32)> {
+    let malformed = || Error::MalformedDevice {
+        line: 0,
+        token: device.to_string(),
+    };
+
+    let (major, minor) = device
+        .split_once(':')
+        .filter(|(major, minor)| is_hex(major) && is_hex(minor))
+        .ok_or_else(malformed)?;
+    let major = u32::from_str_radix(major, 16).map_err(|_| malformed())?;
+    let minor = u32::from_str_radix(minor, 16).map_err(|_| malformed())?;
+
+    Ok((major, minor))
+}
+
+fn split_fields(line: &str) -> (Vec<&str>, &str) {
+    let mut fields: Vec<&str> = Vec::with_capacity(5);
+    let mut rest = line;
+
+    while fields.len() < 5 {
+        rest = rest.trim_start();
+        if rest.is_empty() {
+            break;
+        }
+
+        let end = rest.find(char::is_whitespace).unwrap_or(rest.len());
+        fields.push(&rest[..end]);
+        rest = &rest[end..];
+    }
+
+    (fields, rest.trim())
+}
+
+pub const DEFAULT_PAGE_SIZE: u64 = 4096;
+
+/// Rounds `addr` down to a multiple of `page`. Any non-zero `page` works, not
+/// just powers of two; a `page` of 0 returns `addr` unchanged.
+pub fn align_down(addr: u64, page: u64) -> u64 {
+    match page {
+        0 => addr,
+        _ => addr - addr % page,
+    }
+}
+
+/// Rounds `addr` up to a multiple of `page`, with the same rules for `page` as
+/// `align_down`. Returns `None` if the result does not fit in a `u64`.
+pub fn align_up(addr: u64, page: u64) -> Option<u64> {
+    let down = align_down(addr, page);
+    match down == addr {
+        true => Some(addr),
+        false => down.checked_add(page),
+    }
+}
+
+// Binary units, one decimal place: 4.0K, 1.5M. Sizes below 1K are in bytes.
+pub(crate) fn human_size(bytes: u64) -> String {
+    const UNITS: [char; 6] = ['K', 'M', 'G', 'T', 'P', 'E'];
+
+    if bytes < 1024 {
+        return format!("{}B", bytes);
+    }
+
+    let mut size = bytes as f64 / 1024.0;
+    let mut unit = 0;
+    // Anything from 1023.95 up prints as 1024.0, so move up a unit there:
+    // 1048560 bytes is 1.0M rather than 1024.0K.
+    while size >= 1023.95 && unit < UNITS.len() - 1 {
+        size /= 1024.0;
+        unit += 1;
+    }
+
+    format!("{:.1}{}", size, UNITS[unit])
+}
+
+/// Parses a single maps line. This never panics on arbitrary input, which
+/// makes it a suitable fuzz target. Errors are reported against line 1.
+pub fn parse_line(line: &str) -> Result<Entry> {
+    parse_entry(line)
+        .and_then(|entry| entry.ok_or(Error::TooFewFields { line: 0, count: 0 }))
+        .map_err(|e| e.with_line(1))
+}
+
+// Blank lines yield `Ok(None)`; short lines are `Error::TooFewFields` and left
+// to the caller to skip or surface.
+pub(crate) fn parse_entry(line: &str) -> Result<Option<Entry>> {
+    let (splitted, rest) = split_fields(line);
+
+    match splitted.len() {
+        0 => return Ok(None),
+        1..=4 => {
+            return Err(Error::TooFewFields {
+                line: 0,
+                count: splitted.len(),
+            })
+        }
+        _ => {}
+    }
+
+    let (start_addr, end_addr) = parse_addresses(splitted[0])?;
+    let (start_addr, end_addr) = (Address(start_addr), Address(end_addr));
+    let perms = parse_params(splitted[1])?;
+    let offset = parse_offset(splitted[2])?;
+    let (dev_maj, dev_min) = parse_device(splitted[3])?;
+    let inode = parse_inode(splitted[4])?;
+
+    let (rest, deleted) = match rest.strip_suffix(" (deleted)") {
+        Some(v) => (v, true),
+        None => (rest, false),
+    };
+
+    let path = match rest {
+        "" => None,
+        v => Some(v.to_string()),
+    };
+
+    Ok(Some(Entry {
+        start_addr,
+        end_addr,
+        perms,
+        offset,
+        dev_maj,
+        dev_min,
+        inode,
+        path,
+        deleted,
+    }))
+}
+
+// Only relies on `core` and `alloc`, so these also run with
+// `--no-default-features`.
+#[cfg(test)]
+mod tests {
+    use crate::{parse_line, Address, Error};
+    use alloc::string::ToString;
+
+    #[test]
+    fn line() {
+        let entry = parse_line(
+            "7f3a1c028000-7f3a1c19d000 r-xp 00028000 08:01 1054201 /usr/lib/libc.so.6 (deleted)",
+        )
+        .unwrap();
+        assert_eq!(entry.start_addr, Address(0x7f3a1c028000));
+        assert_eq!(entry.end_addr, 0x7f3a1c19d000);
+        assert_eq!(entry.perms.raw(), "r-xp");
+        assert_eq!(entry.offset, 0x28000);
+        assert_eq!((entry.dev_maj, entry.dev_min), (8, 1));
+        assert_eq!(entry.inode, 1054201);
+        assert_eq!(entry.path.as_deref(), Some("/usr/lib/libc.so.6"));
+        assert!(entry.deleted);
+        assert_eq!(entry.size_human(), "1.5M");
+
+        assert!(matches!(
+            parse_line("7f3a1c028000-7f3a1c19d000 r-zp 00028000 08:01 1054201"),
+            Err(Error::MalformedPermissions { .. })
+        ));
+        assert!(matches!(
+            parse_line("7f3a1c028000-7f3a1c19d000 r-xp"),
+            Err(Error::TooFewFields { count: 2, .. })
+        ));
+        assert_eq!(
+            parse_line("7f3a1c028000-7f3a1c19d000 r-xp")
+                .unwrap_err()
+                .to_string(),
+            "line 1: expected at least 5 fields, found 2"
+        );
+    }
+}
@@ -0,0 +1,2678 @@
+use std::{
+    collections::{BTreeMap, BTreeSet, HashSet},
+    fmt::Debug,
+    fs::File,
+    io::{BufRead, BufReader, Write},
+    ops::{Deref, Index},
+    path::{Path, PathBuf},
+    slice::SliceIndex,
+    thread,
+    time::{Duration, SystemTime},
+};
+
+use crate::{line::parse_entry, Entry, Error, Region, Result};
+
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Entries {
+    entries: Vec<Entry>,
+}
+
+impl Entries {
+    /// Builds an `Entries` from hand-made values, e.g. for test fixtures.
+    ///
+    /// ```
+    /// use linux_maps_parser::{Entries, Entry, Permissions};
+    ///
+    /// let entry = Entry {
+    ///     start_addr: 0x1000.into(),
+    ///     end_addr: 0x2000.into(),
+    ///     perms: "r-xp".parse::<Permissions>().unwrap(),
+    ///     offset: 0,
+    ///     dev_maj: 8,
+    ///     dev_min: 1,
+    ///     inode: 1234,
+    ///     path: Some("/usr/lib/libc.so.6".to_string()),
+    ///     deleted: false,
+    /// };
+    /// let mut heap = entry.clone();
+    /// heap.start_addr = 0x2000.into();
+    /// heap.end_addr = 0x3000.into();
+    /// heap.perms = "rw-p".parse().unwrap();
+    /// heap.path = Some("[heap]".to_string());
+    ///
+    /// let entries = Entries::from_vec(vec![entry, heap]);
+    /// assert_eq!(entries.len(), 2);
+    /// assert_eq!(entries.clone().filter_executable().len(), 1);
+    /// ```
+    pub fn from_vec(entries: Vec<Entry>) -> Entries {
+        Entries { entries }
+    }
+
+    pub fn iter(&self) -> std::slice::Iter<'_, Entry> {
+        self.entries.iter()
+    }
+
+    pub fn as_slice(&self) -> &[Entry] {
+        &self.entries
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Entries are expected to be sorted by address, as the kernel emits them.
+    pub fn find_by_address(&self, addr: u64) -> Option<&Entry> {
+        self.position_by_address(addr).map(|(_, e)| e)
+    }
+
+    /// Like `find_by_address`, but also returns the entry's index so its
+    /// neighbours can be inspected.
+    pub fn position_by_address(&self, addr: u64) -> Option<(usize, &Entry)> {
+        let idx = self.entries.partition_point(|e| e.end_addr <= addr);
+        self.entries
+            .get(idx)
+            .filter(|e| e.contains(addr))
+            .map(|e| (idx, e))
+    }
+
+    pub fn retain<F: FnMut(&Entry) -> bool>(&mut self, f: F) {
+        self.entries.retain(f);
+    }
+
+    /// Adds `entry` the way `mmap` with `MAP_FIXED` would: whatever part of
+    /// an existing mapping it overlaps is dropped, splitting that mapping in
+    /// two if needed. Entries are expected to be sorted by address and stay
+    /// that way.
+    pub fn insert(&mut self, entry: Entry) {
+        let (start, end) = (entry.start_addr, entry.end_addr);
+        let mut entries: Vec<Entry> = Vec::with_capacity(self.entries.len() + 2);
+
+        for old in self.entries.drain(..) {
+            if old.end_addr <= start || end <= old.start_addr {
+                entries.push(old);
+                continue;
+            }
+
+            if old.start_addr < start {
+                let mut left = old.clone();
+                left.end_addr = start;
+                entries.push(left);
+            }
+
+            if end < old.end_addr {
+                let mut right = old.clone();
+                if right.inode != 0 {
+                    right.offset = right.offset.saturating_add(end - old.start_addr);
+                }
+                right.start_addr = end;
+                entries.push(right);
+            }
+        }
+
+        let idx = entries.partition_point(|e| e.start_addr < start);
+        entries.insert(idx, entry);
+        self.entries = entries;
+    }
+
+    pub fn sort_by_address(&mut self) {
+        self.entries.sort_by_key(|e| (e.start_addr, e.end_addr));
+    }
+
+    pub fn sorted_by_size(&self) -> Vec<&Entry> {
+        let mut entries = self.entries.iter().collect::<Vec<&Entry>>();
+        entries.sort_by_key(|e| std::cmp::Reverse(e.size()));
+        entries
+    }
+
+    pub fn total_size(&self) -> u64 {
+        sum_sizes(self.entries.iter())
+    }
+
+    pub fn total_writable_size(&self) -> u64 {
+        sum_sizes(self.entries.iter().filter(|e| e.is_writable()))
+    }
+
+    pub fn total_executable_size(&self) -> u64 {
+        sum_sizes(self.entries.iter().filter(|e| e.is_executable()))
+    }
+
+    pub fn write_csv<W: Write>(&self, mut writer: W) -> std::io::Result<()> {
+        writeln!(writer, "start,end,perms,offset,dev,inode,path")?;
+
+        for e in &self.entries {
+            writeln!(
+                writer,
+                "{:x},{:x},{},{:x},{:02x}:{:02x},{},{}",
+                e.start_addr,
+                e.end_addr,
+                e.perms,
+                e.offset,
+                e.dev_maj,
+                e.dev_min,
+                e.inode,
+                csv_field(e.path.as_deref().unwrap_or(""))
+            )?;
+        }
+
+        Ok(())
+    }
+
+    pub fn to_csv(&self) -> String {
+        let mut csv: Vec<u8> = vec![];
+        // writing into a Vec cannot fail and every field is valid UTF-8
+        self.write_csv(&mut csv).unwrap();
+        String::from_utf8(csv).unwrap()
+    }
+
+    /// One aligned row per entry with the range, permissions, a
+    /// human-readable size and the path, for printing in a terminal.
+    pub fn to_table(&self) -> String {
+        let ranges = self
+            .entries
+            .iter()
+            .map(|e| format!("{:x}-{:x}", e.start_addr, e.end_addr))
+            .collect::<Vec<String>>();
+        let width = ranges.iter().map(String::len).max().unwrap_or(0);
+
+        let mut table = format!("{:<width$}  PERMS  {:>6}  PATH\n", "RANGE", "SIZE");
+        for (e, range) in self.entries.iter().zip(ranges) {
+            let mut path = e.path.clone().unwrap_or_default();
+            if e.deleted {
+                path.push_str(" (deleted)");
+            }
+
+            let row = format!(
+                "{:<width$}  {:<5}  {:>6}  {}",
+                range,
+                e.perms.as_str(),
+                e.size_human(),
+                path
+            );
+            table.push_str(row.trim_end());
+            table.push('\n');
+        }
+
+        table
+    }
+
+    /// Returns true once the number of mappings exceeds 90% of
+    /// `/proc/sys/vm/max_map_count`, past which `mmap` starts failing with
+    /// `ENOMEM`.
+    pub fn is_near_map_limit(&self) -> Result<bool> {
+        let file = File::open(Path::new(PROC_ROOT).join("sys/vm/max_map_count"))
+            .map_err(Error::MaxMapCountUnavailable)?;
+        self.is_near_map_limit_from_reader(BufReader::new(file))
+    }
+
+    /// Like `is_near_map_limit`, reading the limit from `reader`.
+    pub fn is_near_map_limit_from_reader<R: BufRead>(&self, mut reader: R) -> Result<bool> {
+        let mut limit = String::new();
+        reader
+            .read_line(&mut limit)
+            .map_err(Error::MaxMapCountUnavailable)?;
+        let limit = limit.trim().parse::<u64>().map_err(|e| {
+            Error::MaxMapCountUnavailable(std::io::Error::new(std::io::ErrorKind::InvalidData, e))
+        })?;
+
+        Ok((self.len() as u64).saturating_mul(10) > limit.saturating_mul(9))
+    }
+
+    pub fn summary(&self) -> MapsSummary {
+        let count = |f: fn(&Entry) -> bool| self.entries.iter().filter(|e| f(e)).count();
+
+        MapsSummary {
+            total: self.entries.len(),
+            executable: count(Entry::is_executable),
+            writable: count(Entry::is_writable),
+            wx: count(Entry::is_wx),
+            file_backed: count(Entry::is_file_backed),
+            anonymous: count(Entry::is_anonymous),
+            total_size: self.total_size(),
+        }
+    }
+
+    /// Mappings are identified by their address range and path, so a region
+    /// whose permissions changed is not reported.
+    pub fn diff(&self, other: &Entries) -> MapsDiff {
+        let key = |e: &Entry| (e.start_addr, e.end_addr, e.path.clone());
+        let ours = self.entries.iter().map(key).collect::<HashSet<_>>();
+        let theirs = other.entries.iter().map(key).collect::<HashSet<_>>();
+
+        MapsDiff {
+            added: other
+                .entries
+                .iter()
+                .filter(|e| !ours.contains(&key(e)))
+                .cloned()
+                .collect(),
+            removed: self
+                .entries
+                .iter()
+                .filter(|e| !theirs.contains(&key(e)))
+                .cloned()
+                .collect(),
+        }
+    }
+
+    pub fn filter<F: Fn(&Entry) -> bool>(&self, f: F) -> Vec<&Entry> {
+        self.entries
+            .iter()
+            .filter(|e| f(e))
+            .collect::<Vec<&Entry>>()
+    }
+
+    pub fn query(&self) -> Query<'_> {
+        Query {
+            entries: self,
+            predicates: vec![],
+        }
+    }
+
+    /// Entries overlapping any of `ranges`, each listed once in map order.
+    pub fn cover(&self, ranges: &[(u64, u64)]) -> Vec<&Entry> {
+        self.filter(|e| ranges.iter().any(|&(start, end)| overlaps(e, start, end)))
+    }
+
+    /// Entries overlapping `start..end`; an empty window matches nothing.
+    pub fn find_in_range(&self, start: u64, end: u64) -> Vec<&Entry> {
+        self.filter(|e| overlaps(e, start, end))
+    }
+
+    /// Entries are expected to be sorted by address.
+    pub fn gaps(&self) -> Vec<(u64, u64)> {
+        self.entries
+            .windows(2)
+            .filter(|w| w[1].start_addr > w[0].end_addr)
+            .map(|w| (w[0].end_addr.0, w[1].start_addr.0))
+            .collect::<Vec<(u64, u64)>>()
+    }
+
+    /// Pairs of entries whose `[start, end)` ranges overlap, lower start
+    /// first. Always empty for well-formed kernel output.
+    pub fn overlaps(&self) -> Vec<(&Entry, &Entry)> {
+        let mut sorted = self.entries.iter().collect::<Vec<&Entry>>();
+        sorted.sort_by_key(|e| e.start_addr);
+
+        let mut pairs = vec![];
+        for (idx, a) in sorted.iter().enumerate() {
+            for b in sorted[idx + 1..]
+                .iter()
+                .take_while(|b| b.start_addr < a.end_addr)
+            {
+                if b.start_addr < b.end_addr {
+                    pairs.push((*a, *b));
+                }
+            }
+        }
+
+        pairs
+    }
+
+    pub fn largest_free_region(&self) -> Option<(u64, u64)> {
+        self.gaps()
+            .into_iter()
+            .rev()
+            .max_by_key(|(start, end)| end - start)
+    }
+
+    pub fn merge_adjacent(&self) -> Entries {
+        let mut entries: Vec<Entry> = vec![];
+
+        for entry in &self.entries {
+            match entries.last_mut() {
+                Some(last)
+                    if last.end_addr == entry.start_addr
+                        && last.perms == entry.perms
+                        && last.path == entry.path
+                        && last.inode == entry.inode
+                        && (last.dev_maj, last.dev_min) == (entry.dev_maj, entry.dev_min)
+                        && last.deleted == entry.deleted
+                        && (entry.is_anonymous()
+                            || last.offset.checked_add(last.size()) == Some(entry.offset)) =>
+                {
+                    last.end_addr = entry.end_addr;
+                }
+                _ => entries.push(entry.clone()),
+            }
+        }
+
+        Entries { entries }
+    }
+
+    pub fn group_by_path(&self) -> BTreeMap<Option<String>, Vec<&Entry>> {
+        let mut groups: BTreeMap<Option<String>, Vec<&Entry>> = BTreeMap::new();
+
+        for entry in &self.entries {
+            groups.entry(entry.path.clone()).or_default().push(entry);
+        }
+
+        groups
+    }
+
+    pub fn libraries(&self) -> Vec<String> {
+        self.entries
+            .iter()
+            .filter(|e| e.is_file_backed())
+            .filter_map(|e| e.path.as_deref())
+            .filter(|p| p.ends_with(".so") || p.contains(".so."))
+            .collect::<BTreeSet<&str>>()
+            .into_iter()
+            .map(String::from)
+            .collect()
+    }
+
+    pub fn filter_by_pathname(&self, value: &str) -> Vec<&Entry> {
+        self.filter(|e| e.path.as_deref() == Some(value))
+    }
+
+    /// Inode 0 marks mappings with no backing file and matches nothing.
+    pub fn filter_by_inode(&self, inode: u64) -> Vec<&Entry> {
+        self.filter(|e| inode != 0 && e.inode == inode)
+    }
+
+    /// The lowest executable mapping, usually the program's own text.
+    pub fn first_executable(&self) -> Option<&Entry> {
+        self.entries
+            .iter()
+            .filter(|e| e.is_executable())
+            .min_by_key(|e| e.start_addr)
+    }
+
+    /// The highest-addressed mapping. Unlike the slice's `last`, it does not
+    /// rely on the entries being sorted.
+    pub fn last_mapping(&self) -> Option<&Entry> {
+        self.entries.iter().max_by_key(|e| e.start_addr)
+    }
+
+    pub fn heap(&self) -> Option<&Entry> {
+        self.entries.iter().find(|e| e.region() == Region::Heap)
+    }
+
+    /// The main thread's stack; thread stacks are not considered.
+    pub fn stack(&self) -> Option<&Entry> {
+        self.entries.iter().find(|e| e.region() == Region::Stack)
+    }
+
+    /// The lowest address `path` is mapped at, i.e. where the object was
+    /// loaded.
+    pub fn load_base(&self, path: &str) -> Option<u64> {
+        self.filter_by_pathname(path)
+            .into_iter()
+            .map(|e| e.start_addr.0)
+            .min()
+    }
+
+    pub fn code_segment(&self, path: &str) -> Option<&Entry> {
+        self.entries
+            .iter()
+            .find(|e| e.is_executable() && e.path.as_deref() == Some(path))
+    }
+
+    pub fn filter_by_path_contains(&self, substr: &str) -> Vec<&Entry> {
+        self.filter(|e| e.path.as_deref().is_some_and(|p| p.contains(substr)))
+    }
+
+    pub fn filter_by_path_suffix(&self, suffix: &str) -> Vec<&Entry> {
+        self.filter(|e| e.path.as_deref().is_some_and(|p| p.ends_with(suffix)))
+    }
+
+    pub fn find_all_by_pathname(&self, value: &str) -> Vec<Entry> {
+        self.filter_by_pathname(value)
+            .into_iter()
+            .cloned()
+            .collect()
+    }
+
+    pub fn filter_readable(&self) -> Vec<&Entry> {
+        self.filter(Entry::is_readable)
+    }
+
+    pub fn filter_writable(&self) -> Vec<&Entry> {
+        self.filter(Entry::is_writable)
+    }
+
+    pub fn filter_executable(&self) -> Vec<&Entry> {
+        self.filter(Entry::is_executable)
+    }
+
+    pub fn wx_regions(&self) -> Vec<&Entry> {
+        self.filter(Entry::is_wx)
+    }
+
+    /// Entries granting at least the rwx bits set in `mask`, see
+    /// `Permissions::to_octal`.
+    pub fn filter_by_perms_mask(&self, mask: u8) -> Vec<&Entry> {
+        self.filter(|e| e.perms_octal() & mask == mask)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MapsDiff {
+    pub added: Vec<Entry>,
+    pub removed: Vec<Entry>,
+}
+
+impl MapsDiff {
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty()
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Snapshot {
+    pub captured_at: SystemTime,
+    pub entries: Entries,
+}
+
+impl Snapshot {
+    /// Tags `entries` with the current time.
+    pub fn new(entries: Entries) -> Snapshot {
+        Snapshot {
+            captured_at: SystemTime::now(),
+            entries,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MapsSummary {
+    pub total: usize,
+    pub executable: usize,
+    pub writable: usize,
+    pub wx: usize,
+    pub file_backed: usize,
+    pub anonymous: usize,
+    pub total_size: u64,
+}
+
+fn csv_field(value: &str) -> std::borrow::Cow<'_, str> {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\"")).into()
+    } else {
+        value.into()
+    }
+}
+
+fn sum_sizes<'a>(mut entries: impl Iterator<Item = &'a Entry>) -> u64 {
+    entries
+        .try_fold(0u64, |acc, e| acc.checked_add(e.size()))
+        .unwrap_or(u64::MAX)
+}
+
+type EntryPredicate<'a> = Box<dyn Fn(&Entry) -> bool + 'a>;
+
+/// Accumulates constraints on entries and applies them all in a single pass
+/// when collected.
+pub struct Query<'a> {
+    entries: &'a Entries,
+    predicates: Vec<EntryPredicate<'a>>,
+}
+
+impl<'a> Query<'a> {
+    /// Matches entries whose path is `value`, or whose file name is.
+    pub fn path(self, value: &str) -> Self {
+        let value = value.to_string();
+        self.filter(move |e| {
+            e.path.as_deref().is_some_and(|p| {
+                p == value || p.rsplit_once('/').is_some_and(|(_, name)| name == value)
+            })
+        })
+    }
+
+    pub fn readable(self) -> Self {
+        self.filter(Entry::is_readable)
+    }
+
+    pub fn writable(self) -> Self {
+        self.filter(Entry::is_writable)
+    }
+
+    pub fn executable(self) -> Self {
+        self.filter(Entry::is_executable)
+    }
+
+    pub fn min_size(self, size: u64) -> Self {
+        self.filter(move |e| e.size() >= size)
+    }
+
+    pub fn max_size(self, size: u64) -> Self {
+        self.filter(move |e| e.size() <= size)
+    }
+
+    pub fn filter<F: Fn(&Entry) -> bool + 'a>(mut self, f: F) -> Self {
+        self.predicates.push(Box::new(f));
+        self
+    }
+
+    pub fn collect(self) -> Vec<&'a Entry> {
+        self.entries
+            .iter()
+            .filter(|e| self.predicates.iter().all(|p| p(e)))
+            .collect()
+    }
+}
+
+type EntryFilter<'a, I> = std::iter::Filter<I, fn(&&'a Entry) -> bool>;
+
+pub trait EntryIterExt<'a>: Iterator<Item = &'a Entry> + Sized {
+    fn readable(self) -> EntryFilter<'a, Self> {
+        self.filter(|e| e.is_readable())
+    }
+
+    fn writable(self) -> EntryFilter<'a, Self> {
+        self.filter(|e| e.is_writable())
+    }
+
+    fn executable(self) -> EntryFilter<'a, Self> {
+        self.filter(|e| e.is_executable())
+    }
+
+    fn named(self) -> EntryFilter<'a, Self> {
+        self.filter(|e| e.path.is_some())
+    }
+}
+
+impl<'a, I: Iterator<Item = &'a Entry>> EntryIterExt<'a> for I {}
+
+fn overlaps(entry: &Entry, start: u64, end: u64) -> bool {
+    start < end && entry.start_addr < end && start < entry.end_addr
+}
+
+impl Deref for Entries {
+    type Target = [Entry];
+
+    fn deref(&self) -> &[Entry] {
+        &self.entries
+    }
+}
+
+impl<I: SliceIndex<[Entry]>> Index<I> for Entries {
+    type Output = I::Output;
+
+    fn index(&self, index: I) -> &Self::Output {
+        &self.entries[index]
+    }
+}
+
+impl FromIterator<Entry> for Entries {
+    fn from_iter<I: IntoIterator<Item = Entry>>(iter: I) -> Self {
+        Entries {
+            entries: iter.into_iter().collect(),
+        }
+    }
+}
+
+impl IntoIterator for Entries {
+    type Item = Entry;
+    type IntoIter = std::vec::IntoIter<Entry>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.entries.into_iter()
+    }
+}
+
+impl<'a> IntoIterator for &'a Entries {
+    type Item = &'a Entry;
+    type IntoIter = std::slice::Iter<'a, Entry>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.entries.iter()
+    }
+}
+
+pub fn parse(pid: u32) -> Result<Entries> {
+    parse_with_procfs(pid, Path::new(PROC_ROOT))
+}
+
+/// Like `parse`, for a procfs mounted at `proc_root` instead of `/proc`.
+pub fn parse_with_procfs(pid: u32, proc_root: &Path) -> Result<Entries> {
+    parse_procfs(Target::Pid(pid).maps_path(proc_root), false)
+}
+
+// Reading the maps of a live process can race with it changing its address
+// space and leave the final line cut short, and a cut-short line may well
+// still parse. Only procfs reads drop a final line without a newline; readers
+// handed in by the caller are taken as they are.
+fn parse_procfs<P: AsRef<Path>>(path: P, strict: bool) -> Result<Entries> {
+    collect_entries(parse_lines(open(path)?, strict, true))
+}
+
+/// The process or thread whose maps to read.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Target {
+    Pid(u32),
+    /// The calling process, via `/proc/self`.
+    Self_,
+    Thread {
+        pid: u32,
+        tid: u32,
+    },
+}
+
+impl Target {
+    pub fn maps_path(&self, proc_root: &Path) -> PathBuf {
+        self.proc_path(proc_root, "maps")
+    }
+
+    /// The path of `file`, e.g. `smaps`, in this target's procfs directory.
+    pub fn proc_path(&self, proc_root: &Path, file: &str) -> PathBuf {
+        let dir = match self {
+            Target::Pid(pid) => proc_root.join(pid.to_string()),
+            Target::Self_ => proc_root.join("self"),
+            Target::Thread { pid, tid } => proc_root
+                .join(pid.to_string())
+                .join("task")
+                .join(tid.to_string()),
+        };
+        dir.join(file)
+    }
+}
+
+// The `/proc/<pid>/<file>` path every pid-based entry point reads from.
+pub(crate) fn pid_path(pid: u32, file: &str) -> PathBuf {
+    Target::Pid(pid).proc_path(Path::new(PROC_ROOT), file)
+}
+
+const PROC_ROOT: &str = "/proc";
+
+pub fn parse_target(target: Target) -> Result<Entries> {
+    parse_procfs(target.maps_path(Path::new(PROC_ROOT)), false)
+}
+
+pub fn parse_str(pid: &str) -> Result<Entries> {
+    let invalid = || Error::InvalidPid(pid.to_string());
+    let digits = pid.trim();
+    if !digits.bytes().all(|b| b.is_ascii_digit()) {
+        return Err(invalid());
+    }
+    let pid = digits.parse::<u32>().map_err(|_| invalid())?;
+    parse(pid)
+}
+
+pub fn parse_strict(pid: u32) -> Result<Entries> {
+    parse_procfs(pid_path(pid, "maps"), true)
+}
+
+pub fn parse_thread(pid: u32, tid: u32) -> Result<Entries> {
+    parse_target(Target::Thread { pid, tid })
+}
+
+/// Re-reads the maps file up to `attempts` times until every line parses
+/// cleanly in strict mode, returning the last error otherwise.
+pub fn parse_retry(pid: u32, attempts: usize) -> Result<Entries> {
+    let maps_file_name = pid_path(pid, "maps");
+    let mut result = parse_procfs(&maps_file_name, true);
+
+    for _ in 1..attempts {
+        if result.is_ok() {
+            break;
+        }
+        result = parse_procfs(&maps_file_name, true);
+    }
+
+    result
+}
+
+/// Parses the maps of `pid`, recording when the read finished.
+pub fn snapshot(pid: u32) -> Result<Snapshot> {
+    parse(pid).map(Snapshot::new)
+}
+
+pub fn parse_self() -> Result<Entries> {
+    parse_target(Target::Self_)
+}
+
+pub fn parse_file<P: AsRef<Path>>(path: P) -> Result<Entries> {
+    parse_from_reader(open(path)?)
+}
+
+pub(crate) fn open<P: AsRef<Path>>(path: P) -> Result<BufReader<File>> {
+    let file_exists = path.as_ref().exists();
+
+    if !file_exists {
+        return Err(Error::MapsFileDoesNotExist);
+    }
+
+    let file = File::open(path).map_err(Error::from_open)?;
+    Ok(BufReader::new(file))
+}
+
+/// Entries are collected into a vector that starts with room for 256
+/// mappings, which covers most processes without any
+/// reallocation; larger maps grow geometrically from there.
+pub fn parse_from_reader<R: BufRead>(reader: R) -> Result<Entries> {
+    collect_entries(parse_iter(reader))
+}
+
+/// Stops after `max_entries` entries so a pathological process can't make
+/// the caller allocate without bound. The flag is true when entries were
+/// left unread.
+pub fn parse_limited(pid: u32, max_entries: usize) -> Result<(Entries, bool)> {
+    let lines = parse_lines(open(pid_path(pid, "maps"))?, false, true);
+    collect_limited(lines, max_entries)
+}
+
+pub fn parse_limited_from_reader<R: BufRead>(
+    reader: R,
+    max_entries: usize,
+) -> Result<(Entries, bool)> {
+    collect_limited(parse_iter(reader), max_entries)
+}
+
+fn collect_limited(
+    mut iter: impl Iterator<Item = Result<Entry>>,
+    max_entries: usize,
+) -> Result<(Entries, bool)> {
+    let entries = collect_entries(iter.by_ref().take(max_entries))?;
+    let truncated = iter.next().is_some();
+
+    Ok((entries, truncated))
+}
+
+/// A line `parse_collecting` could not turn into an entry.
+#[derive(Debug)]
+pub struct ParseWarning {
+    pub line: usize,
+    /// The offending line, without its trailing newline.
+    pub content: String,
+    pub reason: Error,
+}
+
+/// Parses every line it can and reports the rest as warnings instead of
+/// skipping them silently or failing.
+pub fn parse_collecting(pid: u32) -> Result<(Entries, Vec<ParseWarning>)> {
+    let reader = open(pid_path(pid, "maps"))?;
+    Ok(collect_with_warnings(read_lines(reader, true)))
+}
+
+pub fn parse_collecting_from_reader<R: BufRead>(reader: R) -> (Entries, Vec<ParseWarning>) {
+    collect_with_warnings(read_lines(reader, false))
+}
+
+fn collect_with_warnings(
+    lines: impl Iterator<Item = Result<String>>,
+) -> (Entries, Vec<ParseWarning>) {
+    let mut entries: Vec<Entry> = Vec::with_capacity(ENTRIES_CAPACITY);
+    let mut warnings = vec![];
+
+    for (idx, line) in lines.enumerate() {
+        let line = match line {
+            Ok(line) => line,
+            Err(e) => {
+                warnings.push(ParseWarning {
+                    line: idx + 1,
+                    content: String::new(),
+                    reason: e,
+                });
+                break;
+            }
+        };
+        let line = line.trim_end_matches(['\n', '\r']);
+
+        match parse_entry(line) {
+            Ok(Some(entry)) => entries.push(entry),
+            Ok(None) => {}
+            Err(e) => warnings.push(ParseWarning {
+                line: idx + 1,
+                content: line.to_string(),
+                reason: e.with_line(idx + 1),
+            }),
+        }
+    }
+
+    (Entries { entries }, warnings)
+}
+
+pub fn parse_bytes(data: &[u8]) -> Result<Entries> {
+    parse_from_reader(data)
+}
+
+/// Like `parse_from_reader`, but lines with fewer than 5 fields are reported
+/// as `Error::TooFewFields` instead of being skipped.
+pub fn parse_from_reader_strict<R: BufRead>(reader: R) -> Result<Entries> {
+    collect_entries(parse_lines(reader, true, false))
+}
+
+const ENTRIES_CAPACITY: usize = 256;
+
+fn collect_entries(iter: impl Iterator<Item = Result<Entry>>) -> Result<Entries> {
+    let mut entries: Vec<Entry> = Vec::with_capacity(ENTRIES_CAPACITY);
+
+    for entry in iter {
+        entries.push(entry?);
+    }
+
+    Ok(Entries { entries })
+}
+
+pub fn parse_iter<R: BufRead>(reader: R) -> impl Iterator<Item = Result<Entry>> {
+    parse_lines(reader, false, false)
+}
+
+fn parse_lines<R: BufRead>(
+    reader: R,
+    strict: bool,
+    drop_partial: bool,
+) -> impl Iterator<Item = Result<Entry>> {
+    read_lines(reader, drop_partial)
+        .enumerate()
+        .filter_map(move |(idx, line)| match line {
+            Ok(line) => parse_numbered_line(idx + 1, &line, strict),
+            Err(e) => Some(Err(e)),
+        })
+}
+
+// Yields each line with its newline left on. Paths need not be valid UTF-8,
+// so lines are decoded lossily rather than rejected. A read error is yielded
+// once and ends the iteration. With `drop_partial`, a final line without a
+// newline is taken to be cut short and skipped, see `parse_procfs`.
+pub(crate) fn read_lines<R: BufRead>(
+    mut reader: R,
+    drop_partial: bool,
+) -> impl Iterator<Item = Result<String>> {
+    let mut failed = false;
+    std::iter::from_fn(move || {
+        if failed {
+            return None;
+        }
+
+        let mut line = vec![];
+        match reader.read_until(b'\n', &mut line) {
+            Ok(0) => None,
+            Ok(_) if drop_partial && !line.ends_with(b"\n") => None,
+            Ok(_) => Some(Ok(String::from_utf8_lossy(&line).into_owned())),
+            Err(e) => {
+                failed = true;
+                Some(Err(Error::FileOpenError(e)))
+            }
+        }
+    })
+}
+
+/// An entry together with the line it was parsed from.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SourcedEntry {
+    pub entry: Entry,
+    /// The raw line, without its trailing newline.
+    pub source: String,
+}
+
+/// Like `parse`, but keeps a copy of each raw line next to its entry. Plain
+/// `parse` does not make these extra allocations.
+pub fn parse_with_source(pid: u32) -> Result<Vec<SourcedEntry>> {
+    collect_sourced(read_lines(open(pid_path(pid, "maps"))?, true))
+}
+
+pub fn parse_with_source_from_reader<R: BufRead>(reader: R) -> Result<Vec<SourcedEntry>> {
+    collect_sourced(read_lines(reader, false))
+}
+
+fn collect_sourced(lines: impl Iterator<Item = Result<String>>) -> Result<Vec<SourcedEntry>> {
+    lines
+        .enumerate()
+        .filter_map(|(idx, line)| {
+            let line = match line {
+                Ok(line) => line,
+                Err(e) => return Some(Err(e)),
+            };
+            parse_numbered_line(idx + 1, &line, false).map(|entry| {
+                entry.map(|entry| SourcedEntry {
+                    entry,
+                    source: line.trim_end_matches(['\n', '\r']).to_string(),
+                })
+            })
+        })
+        .collect()
+}
+
+fn parse_numbered_line(number: usize, line: &str, strict: bool) -> Option<Result<Entry>> {
+    let line = line.trim_end_matches(['\n', '\r']);
+
+    match parse_entry(line) {
+        Ok(entry) => entry.map(Ok),
+        Err(Error::TooFewFields { .. }) if !strict => None,
+        Err(e) => Some(Err(e.with_line(number))),
+    }
+}
+
+#[cfg(feature = "tokio")]
+pub async fn parse_async(pid: u32) -> Result<Entries> {
+    use tokio::io::AsyncBufReadExt;
+
+    let mut reader = open_async(pid_path(pid, "maps")).await?;
+
+    let mut entries: Vec<Entry> = Vec::with_capacity(ENTRIES_CAPACITY);
+    let mut line = vec![];
+    let mut number = 0;
+
+    // Decoded the same way as `read_lines`, dropping a cut-short final line.
+    while reader
+        .read_until(b'\n', &mut line)
+        .await
+        .map_err(Error::FileOpenError)?
+        != 0
+        && line.ends_with(b"\n")
+    {
+        number += 1;
+        if let Some(entry) = parse_numbered_line(number, &String::from_utf8_lossy(&line), false) {
+            entries.push(entry?);
+        }
+        line.clear();
+    }
+
+    Ok(Entries { entries })
+}
+
+#[cfg(feature = "tokio")]
+async fn open_async<P: AsRef<Path>>(path: P) -> Result<tokio::io::BufReader<tokio::fs::File>> {
+    let file_exists = tokio::fs::try_exists(&path)
+        .await
+        .map_err(Error::from_open)?;
+
+    if !file_exists {
+        return Err(Error::MapsFileDoesNotExist);
+    }
+
+    let file = tokio::fs::File::open(path)
+        .await
+        .map_err(Error::from_open)?;
+    Ok(tokio::io::BufReader::new(file))
+}
+
+/// Re-parses the maps of `pid` every `interval` and calls `callback` whenever
+/// mappings were added or removed, until the process exits.
+pub fn watch<C: FnMut(&MapsDiff)>(pid: u32, interval: Duration, callback: C) -> Result<()> {
+    watch_with(interval, || parse(pid), callback)
+}
+
+/// Like `watch`, but snapshots come from `fetch`; it stops once `fetch`
+/// returns `Error::MapsFileDoesNotExist`.
+pub fn watch_with<F, C>(interval: Duration, mut fetch: F, mut callback: C) -> Result<()>
+where
+    F: FnMut() -> Result<Entries>,
+    C: FnMut(&MapsDiff),
+{
+    let mut previous = match fetch() {
+        Err(Error::MapsFileDoesNotExist) => return Ok(()),
+        other => other?,
+    };
+
+    loop {
+        thread::sleep(interval);
+
+        let current = match fetch() {
+            Err(Error::MapsFileDoesNotExist) => return Ok(()),
+            other => other?,
+        };
+
+        let diff = previous.diff(&current);
+        if !diff.is_empty() {
+            callback(&diff);
+        }
+        previous = current;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::line::{human_size, parse_addresses, parse_device, parse_params};
+    use crate::maps::ENTRIES_CAPACITY;
+    use crate::{
+        align_down, align_up, parse, parse_bytes, parse_collecting_from_reader, parse_file,
+        parse_from_reader, parse_from_reader_strict, parse_iter, parse_limited_from_reader,
+        parse_line, parse_retry, parse_self, parse_str, parse_target, parse_thread,
+        parse_with_procfs, parse_with_source_from_reader, snapshot, watch, watch_with, Address,
+        Entries, Entry, EntryIterExt, Error, MapsSummary, Permissions, Region, Target,
+    };
+    use std::{fs, io::Cursor, path::Path, time::Duration};
+
+    const MAPS: &str = "\
+55d0c0a00000-55d0c0a02000 r--p 00000000 08:01 1048602 /usr/bin/cat
+55d0c0a02000-55d0c0a07000 r-xp 00002000 08:01 1048602 /usr/bin/cat
+55d0c1c4e000-55d0c1c6f000 rw-p 00000000 00:00 0 [heap]
+7f3a1c000000-7f3a1c028000 r--p 00000000 08:01 1054201 /usr/lib/libc.so.6
+7f3a1c028000-7f3a1c19d000 r-xp 00028000 08:01 1054201 /usr/lib/libc.so.6
+7ffd4b3f1000-7ffd4b412000 rw-p 00000000 00:00 0 [stack]
+";
+
+    fn entry(start_addr: u64, end_addr: u64, perms: &str, path: Option<&str>) -> Entry {
+        Entry {
+            start_addr: start_addr.into(),
+            end_addr: end_addr.into(),
+            perms: perms.parse().unwrap(),
+            offset: 0,
+            dev_maj: 0,
+            dev_min: 0,
+            inode: 0,
+            path: path.map(|v| v.to_string()),
+            deleted: false,
+        }
+    }
+
+    #[test]
+    fn test1() {
+        let parsed = parse(1).unwrap();
+        let heap = parsed.filter_by_pathname("/usr/lib/libc.so.6");
+        println!("{:?}", heap);
+    }
+
+    #[test]
+    fn malformed_address() {
+        match parse_addresses("7f000-") {
+            Err(Error::MalformedAddress { token, .. }) => assert_eq!(token, "7f000-"),
+            other => panic!("unexpected result: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn shared_flag() {
+        assert!(!parse_params("rw-p").unwrap().shared);
+        assert!(parse_params("rw-s").unwrap().shared);
+    }
+
+    #[test]
+    fn reader() {
+        let parsed = parse_from_reader(Cursor::new(MAPS)).unwrap();
+        assert_eq!(parsed.entries.len(), 6);
+        assert_eq!(parsed.entries[2].start_addr, 0x55d0c1c4e000);
+        assert_eq!(parsed.entries[2].path.as_deref(), Some("[heap]"));
+        assert_eq!(parsed.filter_by_pathname("/usr/lib/libc.so.6").len(), 2);
+    }
+
+    #[test]
+    fn reader_malformed_address() {
+        let parsed = parse_from_reader(Cursor::new("7f000- rw-p 00000000 00:00 0\n"));
+        assert!(matches!(parsed, Err(Error::MalformedAddress { .. })));
+    }
+
+    #[test]
+    fn file() {
+        let path = std::env::temp_dir().join(format!("maps-{}", std::process::id()));
+        fs::write(&path, MAPS).unwrap();
+        let parsed = parse_file(&path);
+        fs::remove_file(&path).unwrap();
+        assert_eq!(parsed.unwrap().entries.len(), 6);
+    }
+
+    #[test]
+    fn file_does_not_exist() {
+        let parsed = parse_file("/nonexistent/maps");
+        assert!(matches!(parsed, Err(Error::MapsFileDoesNotExist)));
+    }
+
+    #[test]
+    fn path_with_spaces() {
+        let line = "7f3a1c000000-7f3a1c028000 r--p 00000000 08:01 1054201    /home/user/My Documents/app.so\n";
+        let parsed = parse_from_reader(Cursor::new(line)).unwrap();
+        assert_eq!(
+            parsed.entries[0].path.as_deref(),
+            Some("/home/user/My Documents/app.so")
+        );
+    }
+
+    #[test]
+    fn deleted_mapping() {
+        let line =
+            "7f3a1c000000-7f3a1c028000 r--p 00000000 08:01 1054201 /usr/lib/foo.so (deleted)\n";
+        let parsed = parse_from_reader(Cursor::new(line)).unwrap();
+        assert!(parsed.entries[0].deleted);
+        assert_eq!(parsed.filter_by_pathname("/usr/lib/foo.so").len(), 1);
+
+        let parsed = parse_from_reader(Cursor::new(MAPS)).unwrap();
+        assert!(parsed.entries.iter().all(|e| !e.deleted));
+    }
+
+    #[test]
+    fn iterate() {
+        let parsed = parse_from_reader(Cursor::new(MAPS)).unwrap();
+        assert_eq!(parsed.iter().filter(|e| e.perms.execute).count(), 2);
+        assert_eq!((&parsed).into_iter().count(), 6);
+
+        let paths = parsed
+            .into_iter()
+            .filter_map(|e| e.path)
+            .collect::<Vec<String>>();
+        assert_eq!(paths[0], "/usr/bin/cat");
+        assert_eq!(paths[5], "[stack]");
+    }
+
+    #[test]
+    fn predicates() {
+        let parsed = parse_from_reader(Cursor::new(MAPS)).unwrap();
+        let heap = parsed.filter_by_pathname("[heap]");
+        assert_eq!(heap.len(), 1);
+        assert!(heap[0].is_readable());
+        assert!(heap[0].is_writable());
+        assert!(!heap[0].is_executable());
+        assert!(parsed.filter_by_pathname("[vdso]").is_empty());
+    }
+
+    #[test]
+    fn regions() {
+        let parsed = parse_from_reader(Cursor::new(MAPS)).unwrap();
+        assert_eq!(
+            parsed.entries[0].region(),
+            Region::File("/usr/bin/cat".to_string())
+        );
+        assert_eq!(parsed.entries[2].region(), Region::Heap);
+        assert_eq!(parsed.entries[5].region(), Region::Stack);
+
+        let line = "\
+7ffd4b5f3000-7ffd4b5f5000 r-xp 00000000 00:00 0 [vdso]
+7f3a1c1a0000-7f3a1c1a4000 rw-p 00000000 00:00 0 [anon:scudo:primary]
+7f3a1c1a4000-7f3a1c1a8000 rw-p 00000000 00:00 0
+7f3a1c1a8000-7f3a1c1ac000 rw-p 00000000 00:00 0 [uprobes]
+";
+        let parsed = parse_from_reader(Cursor::new(line)).unwrap();
+        assert_eq!(parsed.entries[0].region(), Region::Vdso);
+        assert_eq!(parsed.entries[1].region(), Region::Anonymous);
+        assert_eq!(parsed.entries[2].region(), Region::Anonymous);
+        assert_eq!(
+            parsed.entries[3].region(),
+            Region::Other("[uprobes]".to_string())
+        );
+    }
+
+    #[test]
+    fn size() {
+        let parsed = parse_from_reader(Cursor::new("1000-2000 r--p 00000000 00:00 0\n")).unwrap();
+        assert_eq!(parsed.entries[0].size(), 0x1000);
+        assert_eq!(entry(0x2000, 0x1000, "r--p", None).size(), 0);
+    }
+
+    #[test]
+    fn find_by_address() {
+        let parsed = parse_from_reader(Cursor::new(MAPS)).unwrap();
+        let entry = parsed.find_by_address(0x7f3a1c100000).unwrap();
+        assert_eq!(entry.start_addr, 0x7f3a1c028000);
+        assert!(entry.contains(0x7f3a1c028000));
+        assert!(!entry.contains(0x7f3a1c19d000));
+        assert!(parsed.find_by_address(0x7f3a1c19d000).is_none());
+        assert!(parsed.find_by_address(0x1000).is_none());
+        assert!(parsed.find_by_address(u64::MAX).is_none());
+    }
+
+    #[test]
+    fn equality() {
+        let parsed = parse_from_reader(Cursor::new(MAPS)).unwrap();
+        let expected = Entry {
+            start_addr: Address(0x55d0c0a00000),
+            end_addr: Address(0x55d0c0a02000),
+            perms: Permissions {
+                read: true,
+                write: false,
+                execute: false,
+                shared: false,
+            },
+            offset: 0,
+            dev_maj: 8,
+            dev_min: 1,
+            inode: 1048602,
+            path: Some("/usr/bin/cat".to_string()),
+            deleted: false,
+        };
+        assert_eq!(parsed.iter().next(), Some(&expected));
+
+        let mut other = expected.clone();
+        other.path = None;
+        assert_ne!(other, expected);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serde_round_trip() {
+        let parsed = parse_from_reader(Cursor::new(MAPS)).unwrap();
+        let json = serde_json::to_string(&parsed).unwrap();
+        assert!(json.contains("\"start_addr\":94355073269760"));
+
+        let restored: crate::Entries = serde_json::from_str(&json).unwrap();
+        assert!(restored.iter().eq(parsed.iter()));
+    }
+
+    #[test]
+    fn display_round_trip() {
+        let lines = [
+            "56015bbd3000-56015bbd5000 r--p 00000000 fe:00 280762                     /usr/bin/head",
+            "7fc4c9787000-7fc4c978a000 rw-s 00000000 00:00 0 ",
+            "7f3a1c000000-7f3a1c028000 r-xp 00028000 08:01 1054201                    /usr/lib/foo.so (deleted)",
+        ];
+        for line in lines {
+            let parsed = parse_from_reader(Cursor::new(line)).unwrap();
+            assert_eq!(format!("{}", parsed.entries[0]), line);
+        }
+    }
+
+    #[test]
+    fn malformed_permissions() {
+        assert!(matches!(
+            "rw".parse::<Permissions>(),
+            Err(Error::MalformedPermissions { .. })
+        ));
+        assert!("rwxq".parse::<Permissions>().is_err());
+        assert!("rw-p-".parse::<Permissions>().is_err());
+
+        let parsed = parse_from_reader(Cursor::new("1000-2000 rw 00000000 00:00 0\n"));
+        assert!(matches!(parsed, Err(Error::MalformedPermissions { .. })));
+    }
+
+    #[test]
+    fn large_inode() {
+        let line = "1000-2000 r--p 00000000 08:01 4294967296 /usr/lib/big.so\n";
+        let parsed = parse_from_reader(Cursor::new(line)).unwrap();
+        assert_eq!(parsed.entries[0].inode, 4294967296);
+
+        let line = "1000-2000 r--p 00000000 08:01 12ab /usr/lib/big.so\n";
+        let parsed = parse_from_reader(Cursor::new(line));
+        assert!(matches!(parsed, Err(Error::MalformedInode { .. })));
+    }
+
+    #[test]
+    fn len_and_index() {
+        let parsed = parse_from_reader(Cursor::new(MAPS)).unwrap();
+        assert_eq!(parsed.len(), MAPS.lines().filter(|l| !l.is_empty()).count());
+        assert!(!parsed.is_empty());
+        assert_eq!(parsed[0].path.as_deref(), Some("/usr/bin/cat"));
+        assert_eq!(parsed[0].start_addr, 0x55d0c0a00000);
+
+        let parsed = parse_from_reader(Cursor::new("\n")).unwrap();
+        assert!(parsed.is_empty());
+    }
+
+    #[test]
+    fn self_maps() {
+        let parsed = parse_self().unwrap();
+        assert!(!parsed.is_empty());
+    }
+
+    #[test]
+    fn permission_filters() {
+        let maps = "\
+1000-2000 r--p 00000000 00:00 0
+2000-3000 r-xp 00000000 00:00 0
+3000-4000 rw-p 00000000 00:00 0
+4000-5000 rwxp 00000000 00:00 0
+5000-6000 ---p 00000000 00:00 0
+";
+        let parsed = parse_from_reader(Cursor::new(maps)).unwrap();
+        let starts = |v: Vec<&Entry>| v.iter().map(|e| e.start_addr.0).collect::<Vec<u64>>();
+        assert_eq!(starts(parsed.filter_executable()), [0x2000, 0x4000]);
+        assert_eq!(starts(parsed.filter_writable()), [0x3000, 0x4000]);
+        assert_eq!(
+            starts(parsed.filter_readable()),
+            [0x1000, 0x2000, 0x3000, 0x4000]
+        );
+        assert_eq!(starts(parsed.filter(|e| e.start_addr >= 0x5000)), [0x5000]);
+    }
+
+    #[test]
+    fn total_sizes() {
+        let entries = Entries {
+            entries: vec![
+                entry(0x1000, 0x3000, "r-xp", None),
+                entry(0x3000, 0x4000, "rw-p", None),
+                entry(0x4000, 0x8000, "rwxp", None),
+            ],
+        };
+        assert_eq!(entries.total_size(), 0x7000);
+        assert_eq!(entries.total_writable_size(), 0x5000);
+        assert_eq!(entries.total_executable_size(), 0x6000);
+
+        let empty = Entries { entries: vec![] };
+        assert_eq!(empty.total_size(), 0);
+
+        let huge = Entries {
+            entries: vec![
+                entry(0, u64::MAX, "r--p", None),
+                entry(0, u64::MAX, "r--p", None),
+            ],
+        };
+        assert_eq!(huge.total_size(), u64::MAX);
+    }
+
+    #[test]
+    fn wx_regions() {
+        let entries = Entries {
+            entries: vec![
+                entry(0x1000, 0x2000, "r-xp", None),
+                entry(0x2000, 0x3000, "rwxp", None),
+                entry(0x3000, 0x4000, "rw-p", None),
+            ],
+        };
+        let wx = entries.wx_regions();
+        assert_eq!(wx.len(), 1);
+        assert_eq!(wx[0].start_addr, 0x2000);
+        assert!(wx[0].is_wx());
+        assert!(!entries[0].is_wx());
+    }
+
+    #[test]
+    fn gaps() {
+        let entries = Entries {
+            entries: vec![
+                entry(0x1000, 0x2000, "r--p", None),
+                entry(0x3000, 0x4000, "r--p", None),
+                entry(0x4000, 0x5000, "r--p", None),
+            ],
+        };
+        assert_eq!(entries.gaps(), [(0x2000, 0x3000)]);
+    }
+
+    #[test]
+    fn merge_adjacent() {
+        let libc = Some("/usr/lib/libc.so.6");
+        let mapped = |start, end, perms, offset| Entry {
+            offset,
+            inode: 1054201,
+            ..entry(start, end, perms, libc)
+        };
+        let entries = Entries {
+            entries: vec![
+                mapped(0x1000, 0x2000, "r-xp", 0x0),
+                mapped(0x2000, 0x3000, "r-xp", 0x1000),
+                mapped(0x3000, 0x4000, "r-xp", 0x2000),
+                mapped(0x4000, 0x5000, "rw-p", 0x3000),
+                mapped(0x6000, 0x7000, "rw-p", 0x5000),
+            ],
+        };
+        let merged = entries.merge_adjacent();
+        assert_eq!(merged.len(), 3);
+        assert_eq!(
+            (merged[0].start_addr.0, merged[0].end_addr.0),
+            (0x1000, 0x4000)
+        );
+        assert_eq!(
+            (merged[1].start_addr.0, merged[1].end_addr.0),
+            (0x4000, 0x5000)
+        );
+        assert_eq!(
+            (merged[2].start_addr.0, merged[2].end_addr.0),
+            (0x6000, 0x7000)
+        );
+
+        // Same path, but the file was replaced after the first mapping.
+        let entries = Entries {
+            entries: vec![
+                Entry {
+                    deleted: true,
+                    ..mapped(0x1000, 0x2000, "r--p", 0x0)
+                },
+                Entry {
+                    inode: 1054202,
+                    ..mapped(0x2000, 0x3000, "r--p", 0x1000)
+                },
+            ],
+        };
+        assert_eq!(entries.merge_adjacent().len(), 2);
+
+        // Adjacent in memory but not in the file.
+        let entries = Entries {
+            entries: vec![
+                mapped(0x1000, 0x2000, "r--p", 0x0),
+                mapped(0x2000, 0x3000, "r--p", 0x4000),
+            ],
+        };
+        assert_eq!(entries.merge_adjacent().len(), 2);
+
+        let entries = Entries {
+            entries: vec![
+                entry(0x1000, 0x2000, "rw-p", None),
+                entry(0x2000, 0x3000, "rw-p", None),
+            ],
+        };
+        assert_eq!(entries.merge_adjacent().len(), 1);
+    }
+
+    #[test]
+    fn address_widths() {
+        assert_eq!(
+            parse_addresses("08048000-08049000").unwrap(),
+            (0x08048000, 0x08049000)
+        );
+        assert_eq!(
+            parse_addresses("ffffffffff600000-ffffffffffffffff").unwrap(),
+            (0xffffffffff600000, u64::MAX)
+        );
+        assert!(matches!(
+            parse_addresses("1ffffffffffffffff-ffffffffffffffff"),
+            Err(Error::MalformedAddress { .. })
+        ));
+        assert!(matches!(
+            parse_addresses("0000000000000000000001000-2000"),
+            Err(Error::MalformedAddress { .. })
+        ));
+    }
+
+    #[test]
+    fn error_line_number() {
+        let maps = "\
+1000-2000 r--p 00000000 00:00 0
+2000-3000 r--p 00000000 00:00 0
+3000-zzzz r--p 00000000 00:00 0
+";
+        match parse_from_reader(Cursor::new(maps)) {
+            Err(Error::MalformedAddress { line, token }) => {
+                assert_eq!(line, 3);
+                assert_eq!(token, "3000-zzzz");
+            }
+            other => panic!("unexpected result: {:?}", other),
+        }
+
+        let maps = "1000-2000 r--p 0000zz00 00:00 0\n";
+        assert!(matches!(
+            parse_from_reader(Cursor::new(maps)),
+            Err(Error::IntParseError { line: 1, .. })
+        ));
+    }
+
+    #[test]
+    fn error_display() {
+        let err = parse_from_reader(Cursor::new("3000-zzzz r--p 00000000 00:00 0\n")).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "line 1: malformed address range '3000-zzzz'"
+        );
+        assert!(std::error::Error::source(&err).is_none());
+
+        let err = parse_from_reader(Cursor::new("1000-2000 r--p zz 00:00 0\n")).unwrap_err();
+        assert!(std::error::Error::source(&err).is_some());
+
+        let boxed: Box<dyn std::error::Error> = Box::new(Error::MapsFileDoesNotExist);
+        assert_eq!(boxed.to_string(), "maps file does not exist");
+    }
+
+    #[test]
+    fn lazy_iter() {
+        let eager = parse_from_reader(Cursor::new(MAPS)).unwrap();
+        let lazy = parse_iter(Cursor::new(MAPS))
+            .collect::<crate::Result<Vec<Entry>>>()
+            .unwrap();
+        assert!(eager.iter().eq(lazy.iter()));
+
+        let maps = format!("{}zzzz-1000 r--p 00000000 00:00 0\n", MAPS);
+        let heap = parse_iter(Cursor::new(maps))
+            .find(|e| matches!(e, Ok(e) if e.path.as_deref() == Some("[heap]")));
+        assert!(heap.is_some());
+    }
+
+    #[test]
+    fn sorting() {
+        let mut entries = Entries {
+            entries: vec![
+                entry(0x5000, 0x6000, "r--p", None),
+                entry(0x1000, 0x4000, "r--p", None),
+                entry(0x4000, 0x5000, "r--p", None),
+            ],
+        };
+        assert_eq!(entries.sorted_by_size()[0].start_addr, 0x1000);
+
+        entries.sort_by_address();
+        let starts = entries.iter().map(|e| e.start_addr.0).collect::<Vec<u64>>();
+        assert_eq!(starts, [0x1000, 0x4000, 0x5000]);
+    }
+
+    #[test]
+    fn group_by_path() {
+        let libc = Some("/usr/lib/libc.so.6");
+        let entries = Entries {
+            entries: vec![
+                entry(0x1000, 0x2000, "r--p", libc),
+                entry(0x2000, 0x4000, "r-xp", libc),
+                entry(0x4000, 0x5000, "rw-p", None),
+            ],
+        };
+        let groups = entries.group_by_path();
+        assert_eq!(groups.len(), 2);
+
+        let libc = &groups[&libc.map(|v| v.to_string())];
+        assert_eq!(libc.len(), 2);
+        assert_eq!(libc.iter().map(|e| e.size()).sum::<u64>(), 0x3000);
+        assert_eq!(groups[&None].len(), 1);
+    }
+
+    #[test]
+    fn device() {
+        assert_eq!(parse_device("fe:01").unwrap(), (0xfe, 0x01));
+        assert_eq!(parse_device("00:00").unwrap(), (0, 0));
+        assert_eq!(parse_device("103:02").unwrap(), (0x103, 0x02));
+        for token in [
+            "", "fe", "fe:", ":01", "zz:01", "fe:01:02", "+8:01", "08:+1",
+        ] {
+            assert!(matches!(
+                parse_device(token),
+                Err(Error::MalformedDevice { .. })
+            ));
+        }
+    }
+
+    #[test]
+    fn strict_mode() {
+        let maps = "\
+1000-2000 r--p 00000000 00:00 0
+
+2000-3000 r--p 00000000
+3000-4000 r--p 00000000 00:00 0
+";
+        let parsed = parse_from_reader(Cursor::new(maps)).unwrap();
+        assert_eq!(parsed.len(), 2);
+
+        match parse_from_reader_strict(Cursor::new(maps)) {
+            Err(Error::TooFewFields { line, count }) => assert_eq!((line, count), (3, 3)),
+            other => panic!("unexpected result: {:?}", other),
+        }
+
+        let parsed = parse_from_reader_strict(Cursor::new(MAPS)).unwrap();
+        assert_eq!(parsed.len(), 6);
+    }
+
+    #[test]
+    fn anonymous_and_file_backed() {
+        let parsed = parse_from_reader(Cursor::new(MAPS)).unwrap();
+        let libc = parsed.filter_by_pathname("/usr/lib/libc.so.6")[0];
+        assert!(libc.is_file_backed());
+        assert!(!libc.is_anonymous());
+        assert!(parsed.filter_by_pathname("[heap]")[0].is_anonymous());
+
+        let parsed = parse_from_reader(Cursor::new("1000-2000 rw-p 00000000 00:00 0\n")).unwrap();
+        assert!(parsed[0].is_anonymous());
+        assert!(!parsed[0].is_file_backed());
+    }
+
+    #[test]
+    fn combined_dev() {
+        let mut e = entry(0x1000, 0x2000, "r--p", None);
+        (e.dev_maj, e.dev_min) = (8, 1);
+        assert_eq!(e.dev(), 0x801);
+
+        (e.dev_maj, e.dev_min) = (0x103, 0x102);
+        assert_eq!(e.dev(), 0x10_0000 | 0x10302);
+    }
+
+    #[test]
+    fn from_iterator() {
+        let parsed = parse_from_reader(Cursor::new(MAPS)).unwrap();
+        let executable = parsed
+            .iter()
+            .filter(|e| e.is_executable())
+            .cloned()
+            .collect::<Entries>();
+        assert_eq!(executable.len(), 2);
+        assert_eq!(executable.clone().len(), 2);
+    }
+
+    #[test]
+    fn reserved_capacity() {
+        let parsed = parse_from_reader(Cursor::new(MAPS)).unwrap();
+        let reserved = parsed.entries.capacity();
+        assert!(reserved >= ENTRIES_CAPACITY);
+
+        let maps = (0..ENTRIES_CAPACITY as u64)
+            .map(|i| {
+                format!(
+                    "{:x}-{:x} r--p 00000000 00:00 0\n",
+                    i * 0x1000,
+                    (i + 1) * 0x1000
+                )
+            })
+            .collect::<String>();
+        let parsed = parse_from_reader(Cursor::new(maps)).unwrap();
+        assert_eq!(parsed.len(), ENTRIES_CAPACITY);
+        assert_eq!(parsed.entries.capacity(), reserved);
+    }
+
+    #[test]
+    fn find_in_range() {
+        let entries = Entries {
+            entries: vec![
+                entry(0x1000, 0x2000, "r--p", None),
+                entry(0x2000, 0x3000, "r--p", None),
+                entry(0x3000, 0x4000, "r--p", None),
+            ],
+        };
+        let found = entries.find_in_range(0x2800, 0x2900);
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].start_addr, 0x2000);
+
+        let found = entries.find_in_range(0x2000, 0x3000);
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].start_addr, 0x2000);
+
+        assert_eq!(entries.find_in_range(0x1fff, 0x3001).len(), 3);
+        assert!(entries.find_in_range(0x4000, 0x5000).is_empty());
+
+        let entries = Entries::from_vec(vec![entry(0x1000, 0x3000, "r--p", None)]);
+        assert!(entries.find_in_range(0x2000, 0x2000).is_empty());
+        assert!(entries.find_in_range(0x2800, 0x2000).is_empty());
+    }
+
+    #[test]
+    fn thread_maps() {
+        let pid = std::process::id();
+        assert!(!parse_thread(pid, pid).unwrap().is_empty());
+        assert!(matches!(
+            parse_thread(pid, u32::MAX),
+            Err(Error::MapsFileDoesNotExist)
+        ));
+    }
+
+    #[test]
+    fn permissions_string() {
+        for perms in ["rw-p", "r-xs", "---p", "rwxs"] {
+            let parsed = perms.parse::<Permissions>().unwrap();
+            assert_eq!(parsed.as_str(), perms);
+            assert_eq!(parsed.to_string(), perms);
+        }
+    }
+
+    #[test]
+    fn csv() {
+        let maps = "\
+55d0c0a00000-55d0c0a02000 r--p 00000000 08:01 1048602 /usr/bin/cat
+7f3a1c000000-7f3a1c028000 r-xp 00028000 08:01 1054201 /opt/a,b/\"lib\".so
+7ffd4b3f1000-7ffd4b412000 rw-p 00000000 00:00 0
+";
+        let parsed = parse_from_reader(Cursor::new(maps)).unwrap();
+        let csv = parsed.to_csv();
+        let lines = csv.lines().collect::<Vec<&str>>();
+        assert_eq!(lines[0], "start,end,perms,offset,dev,inode,path");
+        assert_eq!(
+            lines[1],
+            "55d0c0a00000,55d0c0a02000,r--p,0,08:01,1048602,/usr/bin/cat"
+        );
+        assert_eq!(
+            lines[2],
+            "7f3a1c000000,7f3a1c028000,r-xp,28000,08:01,1054201,\"/opt/a,b/\"\"lib\"\".so\""
+        );
+        assert_eq!(lines[3], "7ffd4b3f1000,7ffd4b412000,rw-p,0,00:00,0,");
+    }
+
+    #[test]
+    fn address_without_dash() {
+        assert!(matches!(
+            parse_addresses("7f00abcd"),
+            Err(Error::MalformedAddress { .. })
+        ));
+        assert!(matches!(
+            parse_addresses("1000-2000-3000"),
+            Err(Error::MalformedAddress { .. })
+        ));
+
+        let parsed = parse_from_reader(Cursor::new("7f00abcd rw-p 00000000 00:00 0\n"));
+        assert!(matches!(
+            parsed,
+            Err(Error::MalformedAddress { line: 1, .. })
+        ));
+    }
+
+    #[test]
+    fn bytes() {
+        let parsed = parse_bytes(MAPS.as_bytes()).unwrap();
+        assert_eq!(parsed.len(), 6);
+
+        let parsed = parse_bytes(b"1000-2000 r--p 00000000 00:00 0\n").unwrap();
+        assert_eq!(parsed.len(), 1);
+    }
+
+    #[test]
+    fn summary() {
+        let mut entries = parse_from_reader(Cursor::new(MAPS)).unwrap();
+        entries
+            .entries
+            .push(entry(0x7ffd4b500000, 0x7ffd4b501000, "rwxp", None));
+        assert_eq!(
+            entries.summary(),
+            MapsSummary {
+                total: 7,
+                executable: 3,
+                writable: 3,
+                wx: 1,
+                file_backed: 4,
+                anonymous: 3,
+                total_size: entries.total_size(),
+            }
+        );
+    }
+
+    #[test]
+    fn page_aligned() {
+        let mut e = entry(0x1000, 0x2000, "r--p", None);
+        e.offset = 0x1000;
+        assert!(e.is_page_aligned(0x1000));
+
+        e.offset = 0x800;
+        assert!(!e.is_page_aligned(0x1000));
+
+        e.offset = 0;
+        e.start_addr = Address(0x1800);
+        assert!(!e.is_page_aligned(0x1000));
+        assert!(!e.is_page_aligned(0));
+    }
+
+    #[test]
+    fn retain() {
+        let maps = format!(
+            "{}7ffd4b5f3000-7ffd4b5f5000 r-xp 00000000 00:00 0 [vdso]\n",
+            MAPS
+        );
+        let mut parsed = parse_from_reader(Cursor::new(maps)).unwrap();
+        parsed.retain(Entry::is_file_backed);
+        assert_eq!(parsed.len(), 4);
+        assert!(parsed
+            .iter()
+            .all(|e| !e.path.as_ref().unwrap().starts_with('[')));
+    }
+
+    #[test]
+    fn diff() {
+        let before = parse_from_reader(Cursor::new(MAPS)).unwrap();
+        let mut after = before.clone();
+        after.entries.remove(2);
+        after.entries.push(entry(
+            0x7f3a1d000000,
+            0x7f3a1d001000,
+            "r-xp",
+            Some("/usr/lib/plugin.so"),
+        ));
+
+        let diff = before.diff(&after);
+        assert_eq!(diff.added.len(), 1);
+        assert_eq!(diff.added[0].path.as_deref(), Some("/usr/lib/plugin.so"));
+        assert_eq!(diff.removed.len(), 1);
+        assert_eq!(diff.removed[0].path.as_deref(), Some("[heap]"));
+
+        assert!(before.diff(&before.clone()).is_empty());
+    }
+
+    #[test]
+    fn address_newtype() {
+        let parsed = parse_from_reader(Cursor::new(MAPS)).unwrap();
+        let e = &parsed[0];
+        assert_eq!(format!("{}", e.start_addr), "0x55d0c0a00000");
+        assert_eq!(format!("{:x}", e.start_addr), "55d0c0a00000");
+        assert_eq!(format!("{:#x}", e.end_addr), "0x55d0c0a02000");
+        assert_eq!(e.end_addr - e.start_addr, 0x2000);
+        assert_eq!(u64::from(e.start_addr + 0x10), 0x55d0c0a00010);
+        assert_eq!(Address::from(0x1000).as_u64(), 0x1000);
+    }
+
+    #[test]
+    fn parse_line_never_panics() {
+        let line =
+            "7f3a1c028000-7f3a1c19d000 r-xp 00028000 08:01 1054201 /usr/lib/libc.so.6 (deleted)";
+        assert!(parse_line(line).is_ok());
+        for end in 0..line.len() {
+            let _ = parse_line(&line[..end]);
+            let _ = parse_line(&line[end..]);
+        }
+
+        // xorshift, to throw reproducible garbage at the parser without a rand dependency
+        let mut state: u64 = 0x2545f4914f6cdd1d;
+        let alphabet = b"0123456789abcdefx-: rwps\t\n[]()/";
+        for _ in 0..10_000 {
+            let len = (state % 96) as usize;
+            let garbage = (0..len)
+                .map(|_| {
+                    state ^= state << 13;
+                    state ^= state >> 7;
+                    state ^= state << 17;
+                    alphabet[(state % alphabet.len() as u64) as usize] as char
+                })
+                .collect::<String>();
+            let _ = parse_line(&garbage);
+        }
+
+        assert!(matches!(
+            parse_line(""),
+            Err(Error::TooFewFields { count: 0, .. })
+        ));
+    }
+
+    #[test]
+    fn truncated_last_line() {
+        let root = std::env::temp_dir().join(format!("truncated-{}", std::process::id()));
+        fs::create_dir_all(root.join("1")).unwrap();
+
+        // The first is too short to parse, the second parses with a cut-short path.
+        let mut results = vec![];
+        for tail in [
+            "7ffd4b5f3000-7ffd4b5f5000 r-xp 0000",
+            "7ffd4b5f3000-7ffd4b5f5000 r-xp 00000000 08:01 1054201 /usr/lib/li",
+        ] {
+            let maps = format!("{}{}", MAPS, tail);
+            fs::write(root.join("1").join("maps"), &maps).unwrap();
+            results.push((maps, parse_with_procfs(1, &root)));
+        }
+        fs::remove_dir_all(&root).unwrap();
+
+        let (maps, parsed) = results.pop().unwrap();
+        assert_eq!(parsed.unwrap().len(), 6);
+        assert_eq!(parse_from_reader(Cursor::new(&maps)).unwrap().len(), 7);
+
+        let (maps, parsed) = results.pop().unwrap();
+        assert_eq!(parsed.unwrap().len(), 6);
+        assert!(parse_from_reader_strict(Cursor::new(&maps)).is_err());
+
+        assert!(matches!(
+            parse_bytes(b"7f000- rw-p 00000000 00:00 0"),
+            Err(Error::MalformedAddress { line: 1, .. })
+        ));
+
+        let maps = format!("{}7ffd4b5f3000-7ffd4b5f5000 r-xp 00000000 08: 0\n", MAPS);
+        assert!(matches!(
+            parse_from_reader(Cursor::new(maps)),
+            Err(Error::MalformedDevice { line: 7, .. })
+        ));
+
+        assert!(!parse_retry(std::process::id(), 3).unwrap().is_empty());
+    }
+
+    #[test]
+    fn code_segment() {
+        let parsed = parse_from_reader(Cursor::new(MAPS)).unwrap();
+        let code = parsed.code_segment("/usr/lib/libc.so.6").unwrap();
+        assert_eq!(code.perms.as_str(), "r-xp");
+        assert_eq!(code.start_addr, 0x7f3a1c028000);
+        assert!(parsed.code_segment("/usr/lib/libssl.so.3").is_none());
+    }
+
+    #[test]
+    fn file_offset() {
+        let parsed = parse_from_reader(Cursor::new(MAPS)).unwrap();
+        let code = parsed.code_segment("/usr/lib/libc.so.6").unwrap();
+        assert_eq!(code.file_offset(0x7f3a1c028000), Some(0x28000));
+        assert_eq!(code.file_offset(0x7f3a1c030123), Some(0x30123));
+        assert_eq!(code.file_offset(0x7f3a1c19d000), None);
+        assert_eq!(code.file_offset(0x1000), None);
+    }
+
+    #[test]
+    fn ordered_sets() {
+        let parsed = parse_from_reader(Cursor::new(MAPS)).unwrap();
+        let set = parsed
+            .iter()
+            .rev()
+            .chain(parsed.iter())
+            .cloned()
+            .collect::<std::collections::BTreeSet<Entry>>();
+        assert_eq!(set.len(), 6);
+        assert!(set.iter().zip(parsed.iter()).all(|(a, b)| a == b));
+
+        let hashed = parsed
+            .iter()
+            .chain(parsed.iter())
+            .collect::<std::collections::HashSet<&Entry>>();
+        assert_eq!(hashed.len(), 6);
+    }
+
+    #[test]
+    fn permissions_length() {
+        for token in ["", "r", "r-x", "r-xpp"] {
+            assert!(matches!(
+                parse_params(token),
+                Err(Error::MalformedPermissions { .. })
+            ));
+        }
+        assert!(!parse_params("---p").unwrap().execute);
+    }
+
+    #[cfg(feature = "tokio")]
+    #[tokio::test]
+    async fn async_self_maps() {
+        let parsed = crate::parse_async(std::process::id()).await.unwrap();
+        assert!(!parsed.is_empty());
+        assert!(matches!(
+            crate::parse_async(u32::MAX).await,
+            Err(Error::MapsFileDoesNotExist)
+        ));
+    }
+
+    #[test]
+    fn find_all_by_pathname() {
+        let owned = {
+            let parsed = parse_from_reader(Cursor::new(MAPS)).unwrap();
+            let owned = parsed.find_all_by_pathname("/usr/lib/libc.so.6");
+            assert!(owned
+                .iter()
+                .eq(parsed.filter_by_pathname("/usr/lib/libc.so.6").into_iter()));
+            owned
+        };
+        assert_eq!(owned.len(), 2);
+    }
+
+    #[test]
+    fn path_substring_and_suffix() {
+        let entries = Entries {
+            entries: vec![
+                entry(0x1000, 0x2000, "r-xp", Some("/usr/lib/libc.so.6")),
+                entry(0x2000, 0x3000, "r-xp", Some("/usr/lib/libc-2.31.so")),
+                entry(0x3000, 0x4000, "r-xp", Some("/usr/bin/cat")),
+                entry(0x4000, 0x5000, "rw-p", None),
+            ],
+        };
+        assert_eq!(entries.filter_by_path_contains("libc").len(), 2);
+        assert_eq!(entries.filter_by_path_contains("/usr/").len(), 3);
+        assert_eq!(entries.filter_by_path_suffix(".so").len(), 1);
+        assert_eq!(entries.filter_by_path_suffix("cat")[0].start_addr, 0x3000);
+    }
+
+    #[test]
+    fn anonymous_trailing_whitespace() {
+        for line in [
+            "7fc4c9787000-7fc4c978a000 rw-p 00000000 00:00 0",
+            "7fc4c9787000-7fc4c978a000 rw-p 00000000 00:00 0 ",
+            "7fc4c9787000-7fc4c978a000 rw-p 00000000 00:00 0   \t",
+        ] {
+            let entry = parse_line(line).unwrap();
+            assert_eq!(entry.path, None);
+            assert!(entry.is_anonymous());
+        }
+    }
+
+    #[test]
+    fn watch_snapshots() {
+        let plugin = format!(
+            "{}7f3a1d000000-7f3a1d001000 r-xp 00000000 08:01 99 /usr/lib/plugin.so\n",
+            MAPS
+        );
+        let mut snapshots = vec![MAPS, MAPS, &plugin, &plugin, MAPS].into_iter();
+        let mut diffs = vec![];
+
+        watch_with(
+            Duration::ZERO,
+            || match snapshots.next() {
+                Some(maps) => parse_from_reader(Cursor::new(maps)),
+                None => Err(Error::MapsFileDoesNotExist),
+            },
+            |diff| diffs.push(diff.clone()),
+        )
+        .unwrap();
+
+        assert_eq!(diffs.len(), 2);
+        assert_eq!(
+            diffs[0].added[0].path.as_deref(),
+            Some("/usr/lib/plugin.so")
+        );
+        assert!(diffs[0].removed.is_empty());
+        assert_eq!(
+            diffs[1].removed[0].path.as_deref(),
+            Some("/usr/lib/plugin.so")
+        );
+
+        assert!(watch(u32::MAX, Duration::ZERO, |_| {}).is_ok());
+    }
+
+    #[test]
+    fn trailing_columns_preserved() {
+        let line = "7f3a1c000000-7f3a1c028000 r--p 00000000 08:01 1054201 /usr/lib/libc.so.6 extra  column";
+        let entry = parse_line(line).unwrap();
+        assert_eq!(
+            entry.path.as_deref(),
+            Some("/usr/lib/libc.so.6 extra  column")
+        );
+        assert!(!entry.deleted);
+    }
+
+    #[test]
+    fn iterator_adapters() {
+        let maps = format!("{}7f3a1d000000-7f3a1d001000 rwxp 00000000 00:00 0\n", MAPS);
+        let parsed = parse_from_reader(Cursor::new(maps)).unwrap();
+        assert_eq!(parsed.iter().executable().count(), 3);
+        assert_eq!(parsed.iter().executable().named().count(), 2);
+        assert_eq!(parsed.iter().writable().named().count(), 2);
+        assert_eq!(parsed.iter().readable().writable().executable().count(), 1);
+    }
+
+    #[test]
+    fn offset_width() {
+        let line = "1000-2000 r--p 10000000000000000 00:00 0";
+        assert!(matches!(
+            parse_line(line),
+            Err(Error::MalformedOffset { .. })
+        ));
+
+        let line = "1000-2000 r--p ffffffffffffffff 00:00 0";
+        assert_eq!(parse_line(line).unwrap().offset, u64::MAX);
+
+        let line = "1000-2000 r--p 00028000 00:00 0";
+        assert_eq!(parse_line(line).unwrap().offset_hex(), "0x28000");
+    }
+
+    #[test]
+    fn libraries() {
+        let maps = format!(
+            "{}7f3a1d000000-7f3a1d001000 r-xp 00000000 08:01 77 /usr/lib/libssl.so.3\n",
+            MAPS
+        );
+        let parsed = parse_from_reader(Cursor::new(maps)).unwrap();
+        assert_eq!(
+            parsed.libraries(),
+            ["/usr/lib/libc.so.6", "/usr/lib/libssl.so.3"]
+        );
+    }
+
+    #[test]
+    fn try_from_str() {
+        let entry = Entry::try_from("1000-2000 r--p 00000000 00:00 0").unwrap();
+        assert_eq!(entry.size(), 0x1000);
+        assert!(matches!(
+            Entry::try_from("1000-2000 r--p"),
+            Err(Error::TooFewFields { line: 1, count: 2 })
+        ));
+    }
+
+    #[test]
+    fn page_count() {
+        let e = entry(0x1000, 0x4000, "r--p", None);
+        assert_eq!(e.page_count(0x1000), 3);
+        assert_eq!(e.page_count_default(), 3);
+        assert_eq!(e.page_count(0x2000), 2);
+        assert_eq!(e.page_count(0), 0);
+    }
+
+    #[test]
+    fn slice_access() {
+        let parsed = parse_from_reader(Cursor::new(MAPS)).unwrap();
+        assert_eq!(parsed.as_slice().len(), parsed.len());
+        assert_eq!(parsed[1..3].len(), 2);
+        assert_eq!(
+            parsed.first().unwrap().path.as_deref(),
+            Some("/usr/bin/cat")
+        );
+        assert!(parsed.windows(2).all(|w| w[0].start_addr < w[1].start_addr));
+    }
+
+    #[test]
+    fn pid_string() {
+        let pid = std::process::id().to_string();
+        assert!(!parse_str(&pid).unwrap().is_empty());
+        assert!(matches!(parse_str("notapid"), Err(Error::InvalidPid(_))));
+        assert!(matches!(parse_str("-1"), Err(Error::InvalidPid(_))));
+        assert!(matches!(parse_str("+1"), Err(Error::InvalidPid(_))));
+    }
+
+    #[test]
+    fn largest_free_region() {
+        let entries = Entries {
+            entries: vec![
+                entry(0x1000, 0x2000, "r--p", None),
+                entry(0x3000, 0x4000, "r--p", None),
+                entry(0x8000, 0x9000, "r--p", None),
+                entry(0xa000, 0xb000, "r--p", None),
+            ],
+        };
+        assert_eq!(entries.largest_free_region(), Some((0x4000, 0x8000)));
+
+        let single = Entries {
+            entries: vec![entry(0x1000, 0x2000, "r--p", None)],
+        };
+        assert_eq!(single.largest_free_region(), None);
+    }
+
+    #[test]
+    fn permission_bits() {
+        assert_eq!(
+            Permissions::from_bits(true, false, true, false).to_octal(),
+            5
+        );
+        assert_eq!(Permissions::from_bits(true, true, true, true).to_octal(), 7);
+        assert_eq!(Permissions::none().to_octal(), 0);
+        assert_eq!(Permissions::none().as_str(), "---p");
+        assert_eq!(
+            Permissions::from_bits(true, true, false, true),
+            "rw-s".parse::<Permissions>().unwrap()
+        );
+    }
+
+    #[test]
+    fn inverted_range() {
+        assert!(matches!(
+            parse_line("2000-1000 r--p 00000000 00:00 0"),
+            Err(Error::InvalidRange {
+                start: 0x2000,
+                end: 0x1000,
+                ..
+            })
+        ));
+        assert!(parse_line("1000-1000 r--p 00000000 00:00 0").is_ok());
+    }
+
+    #[test]
+    fn position_by_address() {
+        let entries = Entries {
+            entries: vec![
+                entry(0x1000, 0x2000, "r--p", None),
+                entry(0x2000, 0x3000, "r-xp", None),
+                entry(0x4000, 0x5000, "rw-p", None),
+            ],
+        };
+        let (idx, e) = entries.position_by_address(0x2abc).unwrap();
+        assert_eq!(idx, 1);
+        assert_eq!(e.start_addr, 0x2000);
+        assert!(entries.position_by_address(0x3000).is_none());
+    }
+
+    #[test]
+    fn with_source() {
+        let parsed = parse_with_source_from_reader(Cursor::new(MAPS)).unwrap();
+        assert_eq!(parsed.len(), 6);
+
+        for (sourced, line) in parsed.iter().zip(MAPS.lines()) {
+            assert_eq!(sourced.source, line);
+            assert_eq!(sourced.entry, parse_line(line).unwrap());
+        }
+    }
+
+    #[test]
+    fn align() {
+        assert_eq!(align_up(0x1001, 0x1000), Some(0x2000));
+        assert_eq!(align_up(0x1000, 0x1000), Some(0x1000));
+        assert_eq!(align_down(0x1fff, 0x1000), 0x1000);
+        assert_eq!(align_up(10, 3), Some(12));
+        assert_eq!(align_down(10, 3), 9);
+        assert_eq!(align_up(0x1234, 0), Some(0x1234));
+        assert_eq!(align_up(u64::MAX, 0x1000), None);
+        assert_eq!(align_up(u64::MAX - 0xfff, 0x1000), Some(u64::MAX - 0xfff));
+
+        // A range too large to round up still counts its partial last page.
+        assert_eq!(entry(0, u64::MAX, "r--p", None).page_count(0x1000), 1 << 52);
+    }
+
+    #[test]
+    fn prefixed_hex() {
+        assert_eq!(parse_addresses("0x7F00-0x7F01").unwrap(), (0x7f00, 0x7f01));
+        assert_eq!(parse_addresses("0X7f00-7F01").unwrap(), (0x7f00, 0x7f01));
+        assert!(parse_addresses("0x-0x7f01").is_err());
+        assert!(parse_addresses("+1000-2000").is_err());
+        assert!(parse_addresses("1000-0x+2000").is_err());
+        assert!(parse_line("+1000-2000 r--p 00000000 +8:+1 1 /a").is_err());
+        assert!(parse_line("1000-2000 r--p +0000000 08:01 1 /a").is_err());
+        assert!(parse_line("1000-2000 r--p 00000000 08:01 +1 /a").is_err());
+
+        let entry = parse_line("0x7F00-0x7F01 r--p 0X1A00 08:01 42 /usr/lib/foo.so").unwrap();
+        assert_eq!(entry.start_addr, 0x7f00);
+        assert_eq!(entry.end_addr, 0x7f01);
+        assert_eq!(entry.offset, 0x1a00);
+    }
+
+    #[test]
+    fn near_map_limit() {
+        let parsed = parse_from_reader(Cursor::new(MAPS)).unwrap();
+
+        assert!(!parsed
+            .is_near_map_limit_from_reader(Cursor::new("65530\n"))
+            .unwrap());
+        assert!(!parsed
+            .is_near_map_limit_from_reader(Cursor::new("7\n"))
+            .unwrap());
+        assert!(parsed
+            .is_near_map_limit_from_reader(Cursor::new("6\n"))
+            .unwrap());
+        assert!(parsed
+            .is_near_map_limit_from_reader(Cursor::new("5"))
+            .unwrap());
+
+        let err = parsed
+            .is_near_map_limit_from_reader(Cursor::new("lots\n"))
+            .unwrap_err();
+        assert!(matches!(err, Error::MaxMapCountUnavailable(_)));
+        assert_eq!(
+            err.to_string(),
+            "could not read vm.max_map_count: invalid digit found in string"
+        );
+        assert!(matches!(
+            parsed.is_near_map_limit_from_reader(Cursor::new(b"\xff\n")),
+            Err(Error::MaxMapCountUnavailable(_))
+        ));
+        assert!(!parsed.is_near_map_limit().unwrap());
+    }
+
+    #[test]
+    fn thread_stack() {
+        let line = "\
+7f3a1b7ff000-7f3a1bfff000 rw-p 00000000 00:00 0 [stack:4242]
+7f3a1bfff000-7f3a1c000000 rw-p 00000000 00:00 0 [stack:oops]
+";
+        let parsed = parse_from_reader(Cursor::new(line)).unwrap();
+        assert_eq!(parsed.entries[0].region(), Region::ThreadStack(4242));
+        assert_eq!(
+            parsed.entries[1].region(),
+            Region::Other("[stack:oops]".to_string())
+        );
+    }
+
+    #[test]
+    fn query() {
+        let parsed = parse_from_reader(Cursor::new(MAPS)).unwrap();
+
+        let libc = parsed
+            .query()
+            .path("libc.so.6")
+            .executable()
+            .min_size(0x1000)
+            .collect();
+        assert_eq!(libc.len(), 1);
+        assert_eq!(libc[0].start_addr, 0x7f3a1c028000);
+
+        let libc = parsed.query().path("/usr/lib/libc.so.6").collect();
+        assert_eq!(libc.len(), 2);
+
+        assert!(parsed.query().path("libc.so").collect().is_empty());
+        assert!(parsed
+            .query()
+            .executable()
+            .min_size(u64::MAX)
+            .collect()
+            .is_empty());
+        assert_eq!(parsed.query().collect().len(), parsed.len());
+    }
+
+    #[test]
+    fn overlaps() {
+        let parsed = parse_from_reader(Cursor::new(MAPS)).unwrap();
+        assert!(parsed.overlaps().is_empty());
+
+        let entries = Entries::from_vec(vec![
+            entry(0x5000, 0x6000, "r--p", None),
+            entry(0x1000, 0x3000, "r--p", None),
+            entry(0x2000, 0x4000, "rw-p", None),
+            entry(0x4000, 0x5000, "rw-p", None),
+        ]);
+        let overlaps = entries.overlaps();
+        assert_eq!(overlaps.len(), 1);
+        assert_eq!(overlaps[0].0.start_addr, 0x1000);
+        assert_eq!(overlaps[0].1.start_addr, 0x2000);
+    }
+
+    #[test]
+    fn permission_denied() {
+        // pid 1 is only readable with elevated privileges, which the test
+        // runner may or may not have.
+        match parse(1) {
+            Ok(_) | Err(Error::PermissionDenied) => {}
+            Err(e) => panic!("unexpected error: {}", e),
+        }
+
+        let denied = std::io::Error::from(std::io::ErrorKind::PermissionDenied);
+        assert!(matches!(Error::from_open(denied), Error::PermissionDenied));
+        let missing = std::io::Error::from(std::io::ErrorKind::NotFound);
+        assert!(matches!(
+            Error::from_open(missing),
+            Error::MapsFileDoesNotExist
+        ));
+    }
+
+    #[test]
+    fn perms_mask() {
+        let entries = Entries::from_vec(vec![
+            entry(0x1000, 0x2000, "r-xp", None),
+            entry(0x2000, 0x3000, "rwxp", None),
+            entry(0x3000, 0x4000, "rw-p", None),
+        ]);
+        assert_eq!(entries[0].perms_octal(), 5);
+
+        let rx = entries.filter_by_perms_mask(0b101);
+        assert_eq!(rx.len(), 2);
+        assert_eq!(rx[0].start_addr, 0x1000);
+        assert_eq!(rx[1].start_addr, 0x2000);
+
+        assert_eq!(entries.filter_by_perms_mask(0).len(), 3);
+    }
+
+    #[test]
+    fn heap_and_stack() {
+        let parsed = parse_from_reader(Cursor::new(MAPS)).unwrap();
+        assert_eq!(parsed.heap().unwrap().start_addr, 0x55d0c1c4e000);
+        assert_eq!(parsed.stack().unwrap().path.as_deref(), Some("[stack]"));
+
+        let entries = Entries::from_vec(vec![
+            entry(0x1000, 0x2000, "r-xp", Some("/usr/bin/cat")),
+            entry(0x2000, 0x3000, "rw-p", Some("[stack:4242]")),
+        ]);
+        assert!(entries.heap().is_none());
+        assert!(entries.stack().is_none());
+    }
+
+    #[test]
+    fn snapshots() {
+        let before = std::time::SystemTime::now();
+        let snap = snapshot(std::process::id()).unwrap();
+        let after = std::time::SystemTime::now();
+        assert!(before <= snap.captured_at && snap.captured_at <= after);
+
+        // Anonymous mappings come and go as other tests allocate, but the
+        // loaded libraries stay put.
+        let parsed = parse(std::process::id()).unwrap();
+        assert!(!snap.entries.is_empty());
+        assert_eq!(snap.entries.libraries(), parsed.libraries());
+    }
+
+    #[test]
+    fn limited() {
+        let (parsed, truncated) = parse_limited_from_reader(Cursor::new(MAPS), 4).unwrap();
+        assert_eq!(parsed.len(), 4);
+        assert!(truncated);
+        assert_eq!(parsed[3].start_addr, 0x7f3a1c000000);
+
+        let (parsed, truncated) = parse_limited_from_reader(Cursor::new(MAPS), 6).unwrap();
+        assert_eq!(parsed.len(), 6);
+        assert!(!truncated);
+
+        let (parsed, truncated) = parse_limited_from_reader(Cursor::new(MAPS), 0).unwrap();
+        assert!(parsed.is_empty());
+        assert!(truncated);
+    }
+
+    #[test]
+    fn cover() {
+        let parsed = parse_from_reader(Cursor::new(MAPS)).unwrap();
+        let covered = parsed.cover(&[
+            (0x7f3a1c028100, 0x7f3a1c028200),
+            (0x55d0c1c4e000, 0x55d0c1c4e010),
+            (0x7f3a1c100000, 0x7f3a1c100001),
+            (0x1000, 0x2000),
+        ]);
+
+        assert_eq!(covered.len(), 2);
+        assert_eq!(covered[0].path.as_deref(), Some("[heap]"));
+        assert_eq!(covered[1].start_addr, 0x7f3a1c028000);
+
+        // Spans the boundary between the two libc mappings.
+        assert_eq!(parsed.cover(&[(0x7f3a1c027000, 0x7f3a1c029000)]).len(), 2);
+        assert!(parsed.cover(&[]).is_empty());
+
+        let entries = Entries::from_vec(vec![entry(0x1000, 0x3000, "r--p", None)]);
+        assert!(entries.cover(&[(0x2000, 0x2000)]).is_empty());
+        assert_eq!(
+            entries.cover(&[(0x2000, 0x2000), (0x2000, 0x2001)]).len(),
+            1
+        );
+    }
+
+    #[test]
+    fn table() {
+        let parsed = parse_from_reader(Cursor::new(MAPS)).unwrap();
+        let table = parsed.to_table();
+        let mut lines = table.lines();
+
+        assert_eq!(
+            lines.next().unwrap(),
+            "RANGE                      PERMS    SIZE  PATH"
+        );
+        assert_eq!(
+            lines.next().unwrap(),
+            "55d0c0a00000-55d0c0a02000  r--p     8.0K  /usr/bin/cat"
+        );
+        assert_eq!(table.lines().count(), 7);
+        assert!(table.contains("132.0K  [heap]"));
+
+        assert_eq!(human_size(0), "0B");
+        assert_eq!(human_size(1023), "1023B");
+        assert_eq!(human_size(1536), "1.5K");
+        assert_eq!(human_size(1048560), "1.0M");
+        assert_eq!(human_size(1048012), "1023.4K");
+        assert_eq!(human_size(3 << 30), "3.0G");
+        assert_eq!(human_size(u64::MAX), "16.0E");
+    }
+
+    #[test]
+    fn size_human() {
+        assert_eq!(entry(0, 0x100000, "r--p", None).size_human(), "1.0M");
+        assert_eq!(entry(0, 0x1000, "r--p", None).size_human(), "4.0K");
+        assert_eq!(entry(0, 0xffff0, "r--p", None).size_human(), "1.0M");
+        assert_eq!(entry(0, 0x60000000, "r--p", None).size_human(), "1.5G");
+        assert_eq!(entry(0x1000, 0x1000, "r--p", None).size_human(), "0B");
+    }
+
+    #[test]
+    fn custom_procfs() {
+        let root = std::env::temp_dir().join(format!("procfs-{}", std::process::id()));
+        fs::create_dir_all(root.join("123")).unwrap();
+        fs::write(root.join("123").join("maps"), MAPS).unwrap();
+
+        let parsed = parse_with_procfs(123, &root);
+        let missing = parse_with_procfs(124, &root);
+        fs::remove_dir_all(&root).unwrap();
+
+        assert_eq!(parsed.unwrap().len(), 6);
+        assert!(matches!(missing, Err(Error::MapsFileDoesNotExist)));
+    }
+
+    #[test]
+    fn load_base() {
+        let libc = Some("/usr/lib/libc.so.6");
+        let entries = Entries::from_vec(vec![
+            entry(0x7f3a1c028000, 0x7f3a1c19d000, "r-xp", libc),
+            entry(0x7f3a1c000000, 0x7f3a1c028000, "r--p", libc),
+            entry(0x7f3a1c19d000, 0x7f3a1c1a0000, "rw-p", libc),
+            entry(0x5000, 0x6000, "r--p", Some("/usr/bin/cat")),
+        ]);
+
+        assert_eq!(
+            entries.load_base("/usr/lib/libc.so.6"),
+            Some(0x7f3a1c000000)
+        );
+        assert_eq!(entries.load_base("/usr/bin/cat"), Some(0x5000));
+        assert_eq!(entries.load_base("/usr/lib/libm.so.6"), None);
+    }
+
+    #[test]
+    fn collecting() {
+        let maps = "\
+55d0c0a00000-55d0c0a02000 r--p 00000000 08:01 1048602 /usr/bin/cat
+55d0c0a02000-55d0c0a06000 r-zp 00002000 08:01 1048602 /usr/bin/cat
+
+55d0c0a06000-55d0c0a08000 r--p
+";
+        let (parsed, warnings) = parse_collecting_from_reader(Cursor::new(maps));
+        assert_eq!(parsed.len(), 1);
+        assert_eq!(warnings.len(), 2);
+
+        assert_eq!(warnings[0].line, 2);
+        assert!(warnings[0].content.contains("r-zp"));
+        assert!(matches!(
+            warnings[0].reason,
+            Error::MalformedPermissions { line: 2, .. }
+        ));
+        assert!(matches!(
+            warnings[1].reason,
+            Error::TooFewFields { line: 4, count: 2 }
+        ));
+    }
+
+    #[test]
+    fn defaults() {
+        assert_eq!(Entry::default().size(), 0);
+        assert_eq!(Entry::default().path, None);
+        assert_eq!(Permissions::default(), Permissions::none());
+
+        let e = Entry {
+            start_addr: 0x1000.into(),
+            end_addr: 0x2000.into(),
+            ..Default::default()
+        };
+        assert_eq!(e.size(), 0x1000);
+        assert_eq!(e.perms.as_str(), "---p");
+    }
+
+    #[test]
+    fn first_executable_and_last_mapping() {
+        let parsed = parse_from_reader(Cursor::new(MAPS)).unwrap();
+        assert_eq!(
+            parsed.first_executable().unwrap().start_addr,
+            0x55d0c0a02000
+        );
+        assert_eq!(
+            parsed.last_mapping().unwrap().path.as_deref(),
+            Some("[stack]")
+        );
+
+        let entries = Entries::from_vec(vec![
+            entry(0x5000, 0x6000, "r-xp", None),
+            entry(0x9000, 0xa000, "rw-p", None),
+            entry(0x1000, 0x2000, "r-xp", None),
+            entry(0x2000, 0x3000, "r--p", None),
+        ]);
+        assert_eq!(entries.first_executable().unwrap().start_addr, 0x1000);
+        assert_eq!(entries.last_mapping().unwrap().start_addr, 0x9000);
+        assert_eq!(entries.last().unwrap().start_addr, 0x2000);
+
+        let empty = Entries::from_vec(vec![]);
+        assert!(empty.first_executable().is_none());
+        assert!(empty.last_mapping().is_none());
+    }
+
+    #[test]
+    fn targets() {
+        let parsed = parse_target(Target::Self_).unwrap();
+        assert!(!parsed.is_empty());
+        assert!(parsed.stack().is_some());
+
+        let pid = std::process::id();
+        assert!(parse_target(Target::Pid(pid)).is_ok());
+        assert!(parse_target(Target::Thread { pid, tid: pid }).is_ok());
+
+        let root = Path::new("/proc");
+        assert_eq!(
+            Target::Thread { pid: 1, tid: 2 }.maps_path(root),
+            Path::new("/proc/1/task/2/maps")
+        );
+        assert_eq!(Target::Self_.maps_path(root), Path::new("/proc/self/maps"));
+        assert_eq!(
+            Target::Pid(7).proc_path(root, "smaps"),
+            Path::new("/proc/7/smaps")
+        );
+    }
+
+    #[test]
+    fn by_inode() {
+        let parsed = parse_from_reader(Cursor::new(MAPS)).unwrap();
+
+        let libc = parsed.filter_by_inode(1054201);
+        assert_eq!(libc.len(), 2);
+        assert!(libc
+            .iter()
+            .all(|e| e.path.as_deref() == Some("/usr/lib/libc.so.6")));
+
+        assert!(parsed.filter_by_inode(0).is_empty());
+        assert!(parsed.filter_by_inode(42).is_empty());
+    }
+
+    #[test]
+    fn insert() {
+        let mut lib = entry(0x1000, 0x5000, "r-xp", Some("/usr/lib/libfoo.so"));
+        lib.inode = 42;
+        let mut entries = Entries::from_vec(vec![
+            lib,
+            entry(0x6000, 0x8000, "rw-p", None),
+            entry(0x9000, 0xa000, "rw-p", None),
+        ]);
+
+        entries.insert(entry(0x2000, 0x3000, "rw-p", None));
+        let ranges = entries
+            .iter()
+            .map(|e| (e.start_addr.0, e.end_addr.0))
+            .collect::<Vec<(u64, u64)>>();
+        assert_eq!(
+            ranges,
+            [
+                (0x1000, 0x2000),
+                (0x2000, 0x3000),
+                (0x3000, 0x5000),
+                (0x6000, 0x8000),
+                (0x9000, 0xa000)
+            ]
+        );
+        assert_eq!(entries[2].offset, 0x2000);
+        assert_eq!(entries[2].path.as_deref(), Some("/usr/lib/libfoo.so"));
+        assert!(entries[1].is_writable());
+
+        // Swallows one mapping whole and trims the ones on either side.
+        entries.insert(entry(0x7000, 0x9800, "r--p", None));
+        let ranges = entries
+            .iter()
+            .map(|e| (e.start_addr.0, e.end_addr.0))
+            .collect::<Vec<(u64, u64)>>();
+        assert_eq!(
+            ranges,
+            [
+                (0x1000, 0x2000),
+                (0x2000, 0x3000),
+                (0x3000, 0x5000),
+                (0x6000, 0x7000),
+                (0x7000, 0x9800),
+                (0x9800, 0xa000)
+            ]
+        );
+        assert!(entries.overlaps().is_empty());
+
+        entries.insert(entry(0, 0x100, "r--p", None));
+        assert_eq!(entries[0].start_addr, 0);
+
+        let mut lib = entry(0x1000, 0x5000, "r--p", Some("/a"));
+        lib.inode = 1;
+        lib.offset = u64::MAX;
+        let mut entries = Entries::from_vec(vec![lib]);
+        entries.insert(entry(0x2000, 0x3000, "rw-p", None));
+        assert_eq!(entries.len(), 3);
+        assert_eq!(entries[2].offset, u64::MAX);
+    }
+
+    #[test]
+    fn raw_permissions() {
+        let none = "---p".parse::<Permissions>().unwrap();
+        assert_eq!(none.raw(), "---p");
+        assert!(!none.read && !none.write && !none.execute);
+
+        for raw in ["r-xp", "rw-s", "-w-p", "rwxs", "--xs"] {
+            assert_eq!(raw.parse::<Permissions>().unwrap().raw(), raw);
+        }
+    }
+
+    #[test]
+    fn non_utf8_and_read_errors() {
+        let maps = b"\
+1000-2000 r--p 00000000 08:01 1 /a
+2000-3000 r--p 00000000 08:01 2 /b\xff
+3000-4000 r--p 00000000 08:01 3 /c
+";
+        let parsed = parse_bytes(maps).unwrap();
+        assert_eq!(parsed.len(), 3);
+        assert_eq!(parsed[1].path.as_deref(), Some("/b\u{fffd}"));
+        assert_eq!(parsed[2].path.as_deref(), Some("/c"));
+
+        struct Failing<'a>(&'a [u8]);
+        impl std::io::Read for Failing<'_> {
+            fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+                match self.0.is_empty() {
+                    true => Err(std::io::Error::other("gone")),
+                    false => std::io::Read::read(&mut self.0, buf),
+                }
+            }
+        }
+
+        let reader = || std::io::BufReader::new(Failing(MAPS.as_bytes()));
+        assert!(matches!(
+            parse_from_reader(reader()),
+            Err(Error::FileOpenError(_))
+        ));
+        let results = parse_iter(reader()).collect::<Vec<_>>();
+        assert_eq!(results.len(), 7);
+        assert!(results[6].is_err());
+
+        let (parsed, warnings) = parse_collecting_from_reader(reader());
+        assert_eq!(parsed.len(), 6);
+        assert!(matches!(warnings[0].reason, Error::FileOpenError(_)));
+    }
+
+    #[test]
+    fn fuzzit() {
+        const PID_MAX: u32 = 4194304;
+        for i in 0..PID_MAX {
+            let _ = parse(i);
+        }
+    }
+}
@@ -0,0 +1,250 @@
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    io::BufRead,
+};
+
+use crate::{
+    line::parse_address,
+    maps::{open, pid_path, read_lines},
+    Address, Error, Result,
+};
+
+#[derive(Debug)]
+pub struct NumaEntries {
+    entries: Vec<NumaEntry>,
+}
+
+impl NumaEntries {
+    pub fn iter(&self) -> std::slice::Iter<'_, NumaEntry> {
+        self.entries.iter()
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+}
+
+impl IntoIterator for NumaEntries {
+    type Item = NumaEntry;
+    type IntoIter = std::vec::IntoIter<NumaEntry>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.entries.into_iter()
+    }
+}
+
+impl<'a> IntoIterator for &'a NumaEntries {
+    type Item = &'a NumaEntry;
+    type IntoIter = std::slice::Iter<'a, NumaEntry>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.entries.iter()
+    }
+}
+
+/// One line of `numa_maps`. Unlike `maps`, only the start of the mapping is
+/// given.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NumaEntry {
+    pub start_addr: Address,
+    /// The memory policy, e.g. `default` or `interleave:0-1`.
+    pub policy: String,
+    /// The backing file, from `file=`.
+    pub path: Option<String>,
+    /// Pages resident on each node, from the `N<node>=<pages>` fields.
+    pub nodes: BTreeMap<u32, u64>,
+    /// The other `key=value` counters such as `mapped`, `anon` or `dirty`.
+    pub counters: BTreeMap<String, u64>,
+    /// Bare keywords such as `heap`, `stack` or `huge`.
+    pub flags: BTreeSet<String>,
+}
+
+impl NumaEntry {
+    pub fn pages_on(&self, node: u32) -> Option<u64> {
+        self.nodes.get(&node).copied()
+    }
+
+    pub fn total_pages(&self) -> u64 {
+        self.nodes.values().sum()
+    }
+
+    pub fn counter(&self, name: &str) -> Option<u64> {
+        self.counters.get(name).copied()
+    }
+}
+
+pub fn parse_numa_maps(pid: u32) -> Result<NumaEntries> {
+    parse_numa_maps_from_reader(open(pid_path(pid, "numa_maps"))?)
+}
+
+pub fn parse_numa_maps_from_reader<R: BufRead>(reader: R) -> Result<NumaEntries> {
+    let mut entries: Vec<NumaEntry> = vec![];
+
+    for (idx, line) in read_lines(reader, false).enumerate() {
+        let line = line?;
+        let line = line.trim_end_matches(['\n', '\r']);
+        let mut fields = line.split_whitespace();
+        let (Some(start), Some(policy)) = (fields.next(), fields.next()) else {
+            continue;
+        };
+
+        let start_addr = parse_address(start).map_err(|e| e.with_line(idx + 1))?;
+
+        let mut entry = NumaEntry {
+            start_addr: Address(start_addr),
+            policy: policy.to_string(),
+            path: None,
+            nodes: BTreeMap::new(),
+            counters: BTreeMap::new(),
+            flags: BTreeSet::new(),
+        };
+
+        for field in fields {
+            let Some((key, value)) = field.split_once('=') else {
+                entry.flags.insert(field.to_string());
+                continue;
+            };
+
+            if key == "file" {
+                entry.path = Some(unescape(value));
+                continue;
+            }
+
+            let value = value
+                .parse::<u64>()
+                .map_err(|source| Error::IntParseError {
+                    line: idx + 1,
+                    source,
+                })?;
+
+            match key.strip_prefix('N').and_then(|n| n.parse::<u32>().ok()) {
+                Some(node) => {
+                    entry.nodes.insert(node, value);
+                }
+                None => {
+                    entry.counters.insert(key.to_string(), value);
+                }
+            }
+        }
+
+        entries.push(entry);
+    }
+
+    Ok(NumaEntries { entries })
+}
+
+// The kernel writes whitespace, `=` and `\` in `file=` paths as `\ooo` octal
+// escapes.
+fn unescape(path: &str) -> String {
+    let bytes = path.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut idx = 0;
+
+    while idx < bytes.len() {
+        let escape = bytes
+            .get(idx + 1..idx + 4)
+            .filter(|digits| {
+                bytes[idx] == b'\\' && digits.iter().all(|d| (b'0'..=b'7').contains(d))
+            })
+            .map(|digits| digits.iter().fold(0u16, |v, d| v * 8 + u16::from(d - b'0')))
+            .and_then(|value| u8::try_from(value).ok());
+
+        match escape {
+            Some(byte) => {
+                out.push(byte);
+                idx += 4;
+            }
+            None => {
+                out.push(bytes[idx]);
+                idx += 1;
+            }
+        }
+    }
+
+    String::from_utf8_lossy(&out).into_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{numa::parse_numa_maps_from_reader, Error};
+    use std::io::Cursor;
+
+    const NUMA_MAPS: &str = "\
+55d0c0a00000 default file=/usr/bin/cat mapped=2 active=0 N0=2 kernelpagesize_kB=4
+55d0c1c4e000 default heap anon=3 dirty=3 N0=1 N1=2 kernelpagesize_kB=4
+7f3a1c028000 interleave:0-1 file=/usr/lib/libc.so.6 mapped=117 mapmax=40 N0=60 N1=57 kernelpagesize_kB=4
+7ffd4b3f1000 default stack anon=4 dirty=4 N1=4 kernelpagesize_kB=4
+";
+
+    #[test]
+    fn nodes() {
+        let parsed = parse_numa_maps_from_reader(Cursor::new(NUMA_MAPS)).unwrap();
+        assert_eq!(parsed.len(), 4);
+
+        let libc = parsed.iter().nth(2).unwrap();
+        assert_eq!(libc.start_addr, 0x7f3a1c028000);
+        assert_eq!(libc.policy, "interleave:0-1");
+        assert_eq!(libc.path.as_deref(), Some("/usr/lib/libc.so.6"));
+        assert_eq!(libc.pages_on(0), Some(60));
+        assert_eq!(libc.pages_on(1), Some(57));
+        assert_eq!(libc.pages_on(2), None);
+        assert_eq!(libc.total_pages(), 117);
+        assert_eq!(libc.counter("mapped"), Some(117));
+        assert_eq!(libc.counter("N0"), None);
+
+        let heap = parsed.iter().nth(1).unwrap();
+        assert!(heap.flags.contains("heap"));
+        assert_eq!(heap.path, None);
+        assert_eq!(heap.counter("kernelpagesize_kB"), Some(4));
+    }
+
+    #[test]
+    fn malformed() {
+        let parsed = parse_numa_maps_from_reader(Cursor::new("\nzzzz default N0=1\n"));
+        assert!(matches!(
+            parsed,
+            Err(Error::MalformedAddress { line: 2, .. })
+        ));
+
+        let parsed = parse_numa_maps_from_reader(Cursor::new("1000 default N0=lots\n"));
+        assert!(matches!(parsed, Err(Error::IntParseError { line: 1, .. })));
+    }
+
+    #[test]
+    fn non_utf8() {
+        let numa_maps = b"\
+1000 default file=/b\xff N0=1
+2000 default file=/c N0=2
+";
+        let parsed = parse_numa_maps_from_reader(Cursor::new(&numa_maps[..])).unwrap();
+        assert_eq!(parsed.len(), 2);
+        assert_eq!(parsed.iter().nth(1).unwrap().pages_on(0), Some(2));
+    }
+
+    #[test]
+    fn escaped_path() {
+        let numa_maps = "\
+1000 default file=/a\\040b N0=1
+2000 default file=/tab\\011and\\134slash\\12 N0=1
+0x3000 default N0=1
+";
+        let parsed = parse_numa_maps_from_reader(Cursor::new(numa_maps)).unwrap();
+        let mut iter = parsed.iter();
+        assert_eq!(iter.next().unwrap().path.as_deref(), Some("/a b"));
+        assert_eq!(
+            iter.next().unwrap().path.as_deref(),
+            Some("/tab\tand\\slash\\12")
+        );
+        assert_eq!(iter.next().unwrap().start_addr, 0x3000);
+
+        let parsed = parse_numa_maps_from_reader(Cursor::new("10000000000000000 default N0=1\n"));
+        assert!(matches!(
+            parsed,
+            Err(Error::MalformedAddress { line: 1, .. })
+        ));
+    }
+}
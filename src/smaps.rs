@@ -0,0 +1,230 @@
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    io::BufRead,
+};
+
+use crate::{
+    line::parse_entry,
+    maps::{open, pid_path, read_lines},
+    Entry, Error, Result,
+};
+
+#[derive(Debug)]
+pub struct SmapsEntries {
+    entries: Vec<SmapsEntry>,
+}
+
+impl SmapsEntries {
+    pub fn iter(&self) -> std::slice::Iter<'_, SmapsEntry> {
+        self.entries.iter()
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+}
+
+impl IntoIterator for SmapsEntries {
+    type Item = SmapsEntry;
+    type IntoIter = std::vec::IntoIter<SmapsEntry>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.entries.into_iter()
+    }
+}
+
+impl<'a> IntoIterator for &'a SmapsEntries {
+    type Item = &'a SmapsEntry;
+    type IntoIter = std::slice::Iter<'a, SmapsEntry>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.entries.iter()
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct SmapsEntry {
+    pub entry: Entry,
+    /// Counters such as `Rss` or `Pss`, in kilobytes as reported by the kernel.
+    pub counters: BTreeMap<String, u64>,
+    pub vm_flags: VmFlags,
+}
+
+/// The two-letter mnemonics from the `VmFlags:` line, e.g. `rd`, `wr`, `ex`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct VmFlags {
+    flags: BTreeSet<String>,
+}
+
+impl VmFlags {
+    pub fn contains(&self, flag: &str) -> bool {
+        self.flags.contains(flag)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &str> {
+        self.flags.iter().map(String::as_str)
+    }
+
+    pub fn len(&self) -> usize {
+        self.flags.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.flags.is_empty()
+    }
+}
+
+impl FromIterator<String> for VmFlags {
+    fn from_iter<I: IntoIterator<Item = String>>(iter: I) -> Self {
+        VmFlags {
+            flags: iter.into_iter().collect(),
+        }
+    }
+}
+
+impl SmapsEntry {
+    pub fn counter(&self, name: &str) -> Option<u64> {
+        self.counters.get(name).copied()
+    }
+
+    /// True for mappings that grow downwards, which is how the main stack
+    /// is set up.
+    pub fn is_growsdown(&self) -> bool {
+        self.vm_flags.contains("gd")
+    }
+}
+
+pub fn parse_smaps(pid: u32) -> Result<SmapsEntries> {
+    parse_smaps_from_reader(open(pid_path(pid, "smaps"))?)
+}
+
+pub fn parse_smaps_from_reader<R: BufRead>(reader: R) -> Result<SmapsEntries> {
+    let mut entries: Vec<SmapsEntry> = vec![];
+
+    for (idx, line) in read_lines(reader, false).enumerate() {
+        let line = line?;
+        let line = line.trim_end_matches(['\n', '\r']);
+        match line.split_once(':') {
+            Some((key, value)) if !key.contains(char::is_whitespace) => {
+                let Some(current) = entries.last_mut() else {
+                    continue;
+                };
+
+                if key == "VmFlags" {
+                    current.vm_flags = value.split_whitespace().map(String::from).collect();
+                    continue;
+                }
+
+                let value = value.trim();
+                let value = value.strip_suffix("kB").unwrap_or(value).trim_end();
+
+                if let Ok(v) = value.parse::<u64>() {
+                    current.counters.insert(key.to_string(), v);
+                }
+            }
+            _ => match parse_entry(line) {
+                Ok(Some(entry)) => entries.push(SmapsEntry {
+                    entry,
+                    counters: BTreeMap::new(),
+                    vm_flags: VmFlags::default(),
+                }),
+                Ok(None) | Err(Error::TooFewFields { .. }) => {}
+                Err(e) => return Err(e.with_line(idx + 1)),
+            },
+        }
+    }
+
+    Ok(SmapsEntries { entries })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::smaps::parse_smaps_from_reader;
+    use std::io::Cursor;
+
+    const SMAPS: &str = "\
+55d0c0a00000-55d0c0a02000 r--p 00000000 08:01 1048602                    /usr/bin/cat
+Size:                  8 kB
+KernelPageSize:        4 kB
+MMUPageSize:           4 kB
+Rss:                   8 kB
+Pss:                   4 kB
+Shared_Clean:          8 kB
+Shared_Dirty:          0 kB
+THPeligible:    0
+VmFlags: rd mr mw me dw sd
+55d0c1c4e000-55d0c1c6f000 rw-p 00000000 00:00 0                          [heap]
+Size:                132 kB
+Rss:                  12 kB
+Pss:                  12 kB
+VmFlags: rd wr mr mw me ac sd
+";
+
+    #[test]
+    fn counters() {
+        let parsed = parse_smaps_from_reader(Cursor::new(SMAPS)).unwrap();
+        assert_eq!(parsed.len(), 2);
+
+        let cat = parsed.iter().next().unwrap();
+        assert_eq!(cat.entry.path.as_deref(), Some("/usr/bin/cat"));
+        assert_eq!(cat.counter("Rss"), Some(8));
+        assert_eq!(cat.counter("Pss"), Some(4));
+        assert_eq!(cat.counter("THPeligible"), Some(0));
+        assert_eq!(cat.counter("VmFlags"), None);
+
+        let heap = parsed.iter().nth(1).unwrap();
+        assert_eq!(heap.entry.start_addr, 0x55d0c1c4e000);
+        assert_eq!(heap.counter("Rss"), Some(12));
+        assert_eq!(heap.counter("Shared_Clean"), None);
+    }
+
+    #[test]
+    fn vm_flags() {
+        let smaps = "\
+7f3a1c028000-7f3a1c19d000 r-xp 00028000 08:01 1054201                    /usr/lib/libc.so.6
+Rss:                 900 kB
+VmFlags: rd ex mr mw me
+";
+        let parsed = parse_smaps_from_reader(Cursor::new(smaps)).unwrap();
+        let flags = &parsed.iter().next().unwrap().vm_flags;
+        assert!(flags.contains("rd"));
+        assert!(flags.contains("ex"));
+        assert!(!flags.contains("wr"));
+        assert_eq!(flags.len(), 5);
+
+        let parsed = parse_smaps_from_reader(Cursor::new(SMAPS)).unwrap();
+        let heap = parsed.iter().nth(1).unwrap();
+        assert!(heap.vm_flags.contains("wr"));
+    }
+
+    #[test]
+    fn growsdown() {
+        let smaps = "\
+7ffd4b3f1000-7ffd4b412000 rw-p 00000000 00:00 0                          [stack]
+Rss:                  24 kB
+VmFlags: rd wr mr mw me gd
+";
+        let parsed = parse_smaps_from_reader(Cursor::new(smaps)).unwrap();
+        assert!(parsed.iter().next().unwrap().is_growsdown());
+
+        let parsed = parse_smaps_from_reader(Cursor::new(SMAPS)).unwrap();
+        assert!(parsed.iter().all(|e| !e.is_growsdown()));
+    }
+
+    #[test]
+    fn non_utf8() {
+        let smaps = b"\
+1000-2000 r--p 00000000 08:01 1 /b\xff
+Rss:                   4 kB
+2000-3000 r--p 00000000 08:01 2 /c
+Rss:                   8 kB
+";
+        let parsed = parse_smaps_from_reader(Cursor::new(&smaps[..])).unwrap();
+        assert_eq!(parsed.len(), 2);
+        assert_eq!(parsed.iter().nth(1).unwrap().counter("Rss"), Some(8));
+    }
+}